@@ -1,21 +1,133 @@
 use crate::core::Position;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+/// how many points a golden apple is worth
+pub const GOLDEN_APPLE_POINTS: usize = 5;
+/// how many points a timed apple is worth, if it's eaten in time
+pub const TIMED_APPLE_POINTS: usize = 3;
+/// how many segments a poison apple takes off the snek
+pub const POISON_APPLE_SEGMENTS: usize = 3;
+/// how many ticks a timed apple lasts before it rots away
+pub const TIMED_APPLE_TICKS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum AppleKind {
+    /// grows the snek by one and scores a point
+    #[default]
+    Plain,
+    /// grows the snek by one and scores bonus points
+    Golden,
+    /// shrinks the snek, which dies if there's nothing left of it
+    Poison,
+    /// worth extra, but rots away if it isn't eaten quickly
+    Timed,
+}
+
+impl AppleKind {
+    /// how many points eating this kind of apple scores
+    pub fn get_points(&self) -> usize {
+        match self {
+            AppleKind::Plain => 1,
+            AppleKind::Golden => GOLDEN_APPLE_POINTS,
+            AppleKind::Poison => 0,
+            AppleKind::Timed => TIMED_APPLE_POINTS,
+        }
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            AppleKind::Plain => "apple",
+            AppleKind::Golden => "golden apple",
+            AppleKind::Poison => "poison apple",
+            AppleKind::Timed => "timed apple",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Apple {
     position: Position,
+    #[serde(default)]
+    kind: AppleKind,
+    /// the tick a timed apple rots away on
+    #[serde(default)]
+    expires_at: Option<usize>,
 }
 
 impl Apple {
     #[tracing::instrument(level = "info")]
     pub fn new(position: Position) -> Self {
-        Self { position }
+        Self::untimed(position, AppleKind::Plain)
+    }
+
+    #[tracing::instrument(level = "info")]
+    pub fn golden(position: Position) -> Self {
+        Self::untimed(position, AppleKind::Golden)
+    }
+
+    #[tracing::instrument(level = "info")]
+    pub fn poison(position: Position) -> Self {
+        Self::untimed(position, AppleKind::Poison)
+    }
+
+    fn untimed(position: Position, kind: AppleKind) -> Self {
+        Self {
+            position,
+            kind,
+            expires_at: None,
+        }
     }
+
+    /// an apple that rots away on tick number `expires_at`
+    #[tracing::instrument(level = "info")]
+    pub fn timed(position: Position, expires_at: usize) -> Self {
+        Self {
+            position,
+            kind: AppleKind::Timed,
+            expires_at: Some(expires_at),
+        }
+    }
+
     #[tracing::instrument(level = "trace")]
     pub fn get_position(&self) -> Position {
         self.position
     }
 
+    pub fn get_kind(&self) -> AppleKind {
+        self.kind
+    }
+
+    pub fn get_expiry(&self) -> Option<usize> {
+        self.expires_at
+    }
+
+    /// whether the apple has rotted away by tick number `tick`
+    pub fn has_expired(&self, tick: usize) -> bool {
+        self.expires_at.is_some_and(|expires_at| tick >= expires_at)
+    }
+
     pub fn will_be_eaten_by(&self, potential_head: &Position) -> bool {
         potential_head == &self.get_position()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_apples_expire() {
+        let apple = Apple::timed(Position::new(1, 1), 10);
+        assert_eq!(apple.get_kind(), AppleKind::Timed);
+        assert!(!apple.has_expired(9));
+        assert!(apple.has_expired(10));
+        assert!(!Apple::new(Position::new(1, 1)).has_expired(usize::MAX));
+    }
+
+    #[test]
+    fn it_reads_apples_from_before_varieties() {
+        let apple: Apple = serde_json::from_str(r#"{"position":{"x":2,"y":3}}"#).unwrap();
+        assert_eq!(apple, Apple::new(Position::new(2, 3)));
+    }
+}
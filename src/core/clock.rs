@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+/// the most time that counts towards ticks between two frames. a frame that
+/// took any longer than this (say the terminal was busy) doesn't make the
+/// game race through a pile of ticks to catch up.
+pub const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+/// keeps a game ticking at its own pace, however often the board is drawn.
+/// the time between frames builds up, and every tick's worth of it that has
+/// built up is a tick to run, so the game goes just as fast whether it's
+/// drawn ten times a second or sixty.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    /// time that's passed that hasn't been ticked away yet
+    banked: Duration,
+    /// when the last frame was, or `None` if the clock is stopped
+    last_frame: Option<Instant>,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// counts the time since the last frame, if the clock's running, and starts it if it isn't
+    pub fn frame(&mut self, now: Instant) {
+        if let Some(last_frame) = self.last_frame {
+            self.elapse(now.saturating_duration_since(last_frame));
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// counts `elapsed` towards the next tick
+    pub fn elapse(&mut self, elapsed: Duration) {
+        self.banked += elapsed.min(MAX_FRAME_TIME);
+    }
+
+    /// stops counting time, like while the game's paused. the
+    /// time from now until the next frame won't count for anything.
+    pub fn stop(&mut self) {
+        self.last_frame = None;
+    }
+
+    /// takes a tick's worth of time if there's that much built up, returning
+    /// whether it's time to tick. call it until it says no, asking for the
+    /// tick length each time, since the game can speed up from one tick to the next.
+    pub fn take_tick(&mut self, tick_duration: Duration) -> bool {
+        if self.banked >= tick_duration {
+            self.banked -= tick_duration;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// how far through the next tick the game is, from 0 to 1
+    pub fn get_progress(&self, tick_duration: Duration) -> f64 {
+        (self.banked.as_secs_f64() / tick_duration.as_secs_f64().max(f64::EPSILON)).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_ticks(clock: &mut Clock, tick_duration: Duration) -> usize {
+        let mut ticks = 0;
+        while clock.take_tick(tick_duration) {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn it_ticks_at_the_same_pace_however_often_it_is_drawn() {
+        let tick = Duration::from_millis(100);
+        for fps in [10, 30, 60, 144] {
+            let mut clock = Clock::new();
+            let mut ticks = 0;
+            for _ in 0..fps * 2 {
+                clock.elapse(Duration::from_secs(1) / fps);
+                ticks += count_ticks(&mut clock, tick);
+            }
+            assert!((19..=20).contains(&ticks), "{} ticks at {} fps", ticks, fps);
+        }
+    }
+
+    #[test]
+    fn slow_frames_dont_pile_up_ticks() {
+        let mut clock = Clock::new();
+        clock.elapse(Duration::from_secs(5));
+        assert_eq!(count_ticks(&mut clock, Duration::from_millis(50)), 5);
+    }
+
+    #[test]
+    fn stopped_clocks_dont_count_the_time() {
+        let mut clock = Clock::new();
+        let start = Instant::now();
+        clock.frame(start);
+        clock.frame(start + Duration::from_millis(60));
+        assert_eq!(clock.get_progress(Duration::from_millis(120)), 0.5);
+        clock.stop();
+        clock.frame(start + Duration::from_secs(60));
+        clock.frame(start + Duration::from_secs(60) + Duration::from_millis(60));
+        assert_eq!(count_ticks(&mut clock, Duration::from_millis(120)), 1);
+    }
+}
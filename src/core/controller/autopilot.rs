@@ -0,0 +1,254 @@
+use crate::core::{AppleKind, Controller, Direction, Position, SlitherResult, State};
+
+use std::collections::VecDeque;
+
+/// finds the shortest way to an apple, but only takes it if the
+/// snek could still reach its own tail after eating. otherwise
+/// it follows its tail around until the way is clear, or until
+/// it's gone round the whole board and decides to risk it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Autopilot {
+    /// how many ticks it's been following its tail
+    dawdled: usize,
+}
+
+impl Autopilot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Controller for Autopilot {
+    fn steer(&mut self, state: &State, player: usize) -> Option<Direction> {
+        let (width, height) = state.get_walls().get_dimensions();
+        if let Some(path) = find_path(state, player, |position| is_edible(state, position)) {
+            let mut future = state.fork();
+            let survives = follow(&mut future, player, &path);
+            let is_safe = future.is_game_over()
+                || count_steps_to_tail(&future, player).is_some_and(|steps| steps > 1);
+            if survives && (is_safe || self.dawdled > width * height) {
+                self.dawdled = 0;
+                return path.first().copied();
+            }
+        }
+        self.dawdled += 1;
+        let heading = state.get_players()[player]
+            .get_snek()
+            .get_head()
+            .get_direction();
+        let moves: Vec<(Direction, State)> = Direction::ALL
+            .into_iter()
+            .filter(|d| !d.is_on_a_dime(&heading))
+            .filter_map(|d| {
+                let mut future = state.fork();
+                follow_one(&mut future, player, d).then_some((d, future))
+            })
+            .collect();
+        // dawdle towards the tail, the long way round, to leave the apple time
+        // to come free. going round in circles never frees some apples though,
+        // so after a while it cuts across towards the apple instead.
+        let is_impatient = self.dawdled > width * height;
+        let chase_tail = moves
+            .iter()
+            .filter_map(|(d, future)| {
+                let steps = count_steps_to_tail(future, player).filter(|steps| *steps > 1)?;
+                let head = future.get_players()[player].get_snek().get_head();
+                let eagerness = if is_impatient {
+                    usize::MAX - distance_to_apple(state, &head.get_position())
+                } else {
+                    steps
+                };
+                Some((*d, eagerness))
+            })
+            .max_by_key(|(_, eagerness)| *eagerness);
+        if let Some((direction, _)) = chase_tail {
+            return Some(direction);
+        }
+        // trapped, so head wherever there's the most room and hope the tail moves out of the way
+        moves
+            .iter()
+            .max_by_key(|(_, future)| count_room(future, player))
+            .map(|(d, _)| *d)
+    }
+}
+
+/// apples that grow the snek, which isn't all of them
+fn is_edible(state: &State, position: &Position) -> bool {
+    state
+        .get_apples()
+        .iter()
+        .any(|a| a.get_position() == *position && a.get_kind() != AppleKind::Poison)
+}
+
+/// how far `position` is from the nearest apple, as the crow flies
+fn distance_to_apple(state: &State, position: &Position) -> usize {
+    let (x, y) = position.get_coordinates();
+    state
+        .get_apples()
+        .iter()
+        .map(|a| {
+            let (apple_x, apple_y) = a.get_position().get_coordinates();
+            x.abs_diff(apple_x) + y.abs_diff(apple_y)
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// which cells a snek can't slither through right now: walls, every
+/// snek on the board and poison apples
+fn find_obstacles(state: &State) -> Vec<bool> {
+    let walls = state.get_walls();
+    let (width, height) = walls.get_dimensions();
+    let mut obstacles = vec![false; width * height];
+    for (i, obstacle) in obstacles.iter_mut().enumerate() {
+        *obstacle = !walls.is_position_inside(&Position::new(i % width, i / width));
+    }
+    let sneks = state
+        .get_players()
+        .iter()
+        .filter(|p| p.is_alive())
+        .flat_map(|p| p.get_snek().get_segment_positions());
+    let poison = state
+        .get_apples()
+        .iter()
+        .filter(|a| a.get_kind() == AppleKind::Poison)
+        .map(|a| a.get_position());
+    for position in sneks.chain(poison) {
+        let (x, y) = position.get_coordinates();
+        obstacles[x + y * width] = true;
+    }
+    obstacles
+}
+
+/// the shortest way from `player`'s head to a cell that `is_goal`,
+/// without going through any obstacles. the goal itself can be one.
+fn find_path(
+    state: &State,
+    player: usize,
+    mut is_goal: impl FnMut(&Position) -> bool,
+) -> Option<Vec<Direction>> {
+    let walls = state.get_walls();
+    let (width, height) = walls.get_dimensions();
+    let obstacles = find_obstacles(state);
+    let head = state.get_players()[player].get_snek().get_head();
+    let mut came_from: Vec<Option<(Position, Direction)>> = vec![None; obstacles.len()];
+    let mut visited = vec![false; obstacles.len()];
+    let (x, y) = head.get_position().get_coordinates();
+    visited[x + y * width] = true;
+    let mut frontier = VecDeque::from([head.get_position()]);
+    while let Some(position) = frontier.pop_front() {
+        for direction in Direction::ALL {
+            if position == head.get_position() && direction.is_on_a_dime(&head.get_direction()) {
+                continue;
+            }
+            let next = match walls.neighbor(&position, direction) {
+                Some(next) => next,
+                None => continue,
+            };
+            let (x, y) = next.get_coordinates();
+            let i = x + y * width;
+            if x >= width || y >= height || visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            came_from[i] = Some((position, direction));
+            if is_goal(&next) {
+                let mut path = vec![direction];
+                let mut position = position;
+                while let Some((previous, direction)) = {
+                    let (x, y) = position.get_coordinates();
+                    came_from[x + y * width]
+                } {
+                    path.push(direction);
+                    position = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if !obstacles[i] {
+                frontier.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// plays `path` out on `future`, returning whether the snek survived it
+fn follow(future: &mut State, player: usize, path: &[Direction]) -> bool {
+    for direction in path {
+        if !follow_one(future, player, *direction) {
+            return false;
+        }
+        if future.is_game_over() {
+            return true;
+        }
+    }
+    true
+}
+
+/// moves one step on `future`, returning whether the snek survived it
+fn follow_one(future: &mut State, player: usize, direction: Direction) -> bool {
+    future.set_course(player, direction);
+    !matches!(future.tick_all()[player], SlitherResult::Died(_))
+}
+
+/// how many steps the head is from the tail, if it can get there at all
+fn count_steps_to_tail(state: &State, player: usize) -> Option<usize> {
+    if state.is_game_over() {
+        return Some(0);
+    }
+    let tail = state.get_players()[player].get_snek().get_segments()[0].get_position();
+    find_path(state, player, |position| *position == tail).map(|path| path.len())
+}
+
+/// how many cells the head could get to
+fn count_room(state: &State, player: usize) -> usize {
+    let mut room = 0;
+    find_path(state, player, |_| {
+        room += 1;
+        false
+    });
+    room
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Apple, GameOptions, Snek, Walls};
+
+    #[test]
+    fn it_eats_most_of_the_board() {
+        let mut state = State::with_seed(12, 12, 7);
+        let mut autopilot = Autopilot::new();
+        while !state.is_game_over() && state.get_tick() < 5_000 {
+            if let Some(direction) = autopilot.steer(&state, 0) {
+                state.turn_snek(direction);
+            }
+            state.tick();
+        }
+        assert!(state.get_score() >= 60, "only scored {}", state.get_score());
+    }
+
+    #[test]
+    fn it_wont_eat_its_way_into_a_dead_end() {
+        // a corridor along the top with a dead end the apple sits at
+        // the bottom of, with a wall on either side of it
+        //
+        //   ##########
+        //   #........#
+        //   ####.#####
+        //   ####a#####
+        //   ##########
+        let mut walls = Walls::new(10, 5);
+        for x in 1..9 {
+            if x != 4 {
+                walls.add_wall(Position::new(x, 2));
+                walls.add_wall(Position::new(x, 3));
+            }
+        }
+        let snek = Snek::line_snek(Position::new(2, 1), Direction::Right, 3);
+        let apples = vec![Apple::new(Position::new(4, 3))];
+        let state = State::from_parts(walls, vec![snek], apples, 1, GameOptions::default());
+        assert_eq!(Autopilot::new().steer(&state, 0), Some(Direction::Right));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::core::{Controller, Direction, Position, State};
+
+/// heads for the nearest apple, without running into anything if it
+/// can help it. it doesn't look any further ahead than the next cell,
+/// so it's easy to trap.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Greedy;
+
+impl Controller for Greedy {
+    fn steer(&mut self, state: &State, player: usize) -> Option<Direction> {
+        let head = state.get_players()[player].get_snek().get_head();
+        let is_blocked =
+            |position: &Position| state.is_wall(position) || state.is_snek_at(position);
+        let distance_to_apple = |position: &Position| {
+            let (x, y) = position.get_coordinates();
+            state
+                .get_apples()
+                .iter()
+                .map(|a| {
+                    let (apple_x, apple_y) = a.get_position().get_coordinates();
+                    x.abs_diff(apple_x) + y.abs_diff(apple_y)
+                })
+                .min()
+                .unwrap_or(0)
+        };
+        Direction::ALL
+            .into_iter()
+            .filter(|d| !d.is_on_a_dime(&head.get_direction()))
+            .filter_map(|d| {
+                let next = state.get_walls().neighbor(&head.get_position(), d)?;
+                (!is_blocked(&next)).then(|| (d, distance_to_apple(&next)))
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(direction, _)| direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SlitherResult;
+
+    #[test]
+    fn it_eats_the_apple() {
+        let mut state = State::with_seed(20, 20, 5);
+        let mut greedy = Greedy;
+        for _ in 0..60 {
+            if let Some(direction) = greedy.steer(&state, 0) {
+                state.turn_snek(direction);
+            }
+            if let SlitherResult::Died(cause) = state.tick() {
+                panic!("greedy died because {}", cause.describe());
+            }
+        }
+        assert!(state.get_score() > 0);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::core::{AppleKind, Controller, Direction, Position, State, Walls};
+
+use std::fmt::{self, Display};
+
+/// follows a path that visits every cell on the board once and comes
+/// back round, so it always has somewhere to go and eats every apple
+/// eventually. it cuts across the cycle towards the apple whenever
+/// that doesn't take it past its own tail. it plays as if it's alone
+/// on the board, and only steers round other sneks and poison apples
+/// on shortcuts, so they can still get the better of it.
+#[derive(Debug, Clone)]
+pub struct Hamiltonian {
+    width: usize,
+    /// how far round the cycle each cell is, or `None` for walls
+    order: Vec<Option<usize>>,
+    /// every cell on the board, in the order the cycle visits them
+    cycle: Vec<Position>,
+}
+
+/// why a board can't be played by following a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoCycle {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Display for NoCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "there's no way round a {}x{} board that visits every cell once. \
+             the free cells need to make a rectangle at least two cells wide \
+             and tall, with an even number of rows or columns.",
+            self.width, self.height
+        )
+    }
+}
+
+impl std::error::Error for NoCycle {}
+
+impl Hamiltonian {
+    /// works out a cycle round the board. the free cells have to make
+    /// a rectangle, which is what classic and wraparound boards are.
+    pub fn new(walls: &Walls) -> Result<Self, NoCycle> {
+        let (width, height) = walls.get_dimensions();
+        let no_cycle = NoCycle { width, height };
+        let free = walls.get_free_positions();
+        let xs = free.iter().map(|p| p.get_coordinates().0);
+        let ys = free.iter().map(|p| p.get_coordinates().1);
+        let (left, right) = (
+            xs.clone().min().ok_or_else(|| no_cycle.clone())?,
+            xs.max().unwrap(),
+        );
+        let (top, bottom) = (ys.clone().min().unwrap(), ys.max().unwrap());
+        let (columns, rows) = (right - left + 1, bottom - top + 1);
+        if free.len() != columns * rows || columns < 2 || rows < 2 {
+            return Err(no_cycle);
+        }
+        let cycle: Vec<Position> = if rows % 2 == 0 {
+            snake_through(columns, rows)
+                .map(|(x, y)| Position::new(left + x, top + y))
+                .collect()
+        } else if columns % 2 == 0 {
+            snake_through(rows, columns)
+                .map(|(y, x)| Position::new(left + x, top + y))
+                .collect()
+        } else {
+            return Err(no_cycle);
+        };
+        let mut order = vec![None; width * height];
+        for (i, position) in cycle.iter().enumerate() {
+            let (x, y) = position.get_coordinates();
+            order[x + y * width] = Some(i);
+        }
+        Ok(Self {
+            width,
+            order,
+            cycle,
+        })
+    }
+
+    fn get_order(&self, position: &Position) -> Option<usize> {
+        let (x, y) = position.get_coordinates();
+        if x >= self.width {
+            return None;
+        }
+        *self.order.get(x + y * self.width)?
+    }
+
+    /// how many steps round the cycle it is from one cell to another
+    fn count_steps(&self, from: usize, to: usize) -> usize {
+        (to + self.cycle.len() - from) % self.cycle.len()
+    }
+}
+
+/// a cycle through every cell of a `columns` x `rows` rectangle, which
+/// has to have an even number of rows. it goes along the top row, weaves
+/// back and forth through the rest of the rows, then back up the first column.
+fn snake_through(columns: usize, rows: usize) -> impl Iterator<Item = (usize, usize)> {
+    let top_row = (0..columns).map(|x| (x, 0));
+    let rest = (1..rows).flat_map(move |y| {
+        let xs: Vec<usize> = match y % 2 {
+            1 => (1..columns).rev().collect(),
+            _ => (1..columns).collect(),
+        };
+        xs.into_iter().map(move |x| (x, y))
+    });
+    let first_column = (1..rows).rev().map(|y| (0, y));
+    top_row.chain(rest).chain(first_column)
+}
+
+impl Controller for Hamiltonian {
+    fn steer(&mut self, state: &State, player: usize) -> Option<Direction> {
+        let snek = state.get_players()[player].get_snek();
+        let head = snek.get_head();
+        let at = self.get_order(&head.get_position())?;
+        let tail = self.get_order(&snek.get_segments()[0].get_position())?;
+        // the body only ever lies behind the head, so every cell between
+        // the head and the tail going forward round the cycle is free.
+        // a snek on its own can go anywhere but right back where it came from.
+        let length = snek.count_segments();
+        let room = match length {
+            1 => self.cycle.len() - 1,
+            _ => self.count_steps(at, tail),
+        };
+        let is_blocked = |position: &Position| {
+            state
+                .get_apples()
+                .iter()
+                .any(|a| a.get_position() == *position && a.get_kind() == AppleKind::Poison)
+                || (0..state.get_players().len())
+                    .filter(|other| *other != player)
+                    .any(|other| state.is_player_at(other, position))
+        };
+        let is_edible = |position: &Position| {
+            state
+                .get_apples()
+                .iter()
+                .any(|a| a.get_position() == *position && a.get_kind() != AppleKind::Poison)
+        };
+        let apple = state
+            .get_apples()
+            .iter()
+            .filter(|a| a.get_kind() != AppleKind::Poison)
+            .filter_map(|a| self.get_order(&a.get_position()))
+            .min_by_key(|apple| self.count_steps(at, *apple));
+        let moves: Vec<(Direction, usize)> = Direction::ALL
+            .into_iter()
+            .filter(|d| !d.is_on_a_dime(&head.get_direction()))
+            .filter_map(|d| {
+                let next = state.get_walls().neighbor(&head.get_position(), d)?;
+                let steps = self.count_steps(at, self.get_order(&next)?);
+                // the snek can't slither into the tip of its tail, so it always
+                // needs a spare cell ahead of it, even once it's grown
+                let spare = if is_edible(&next) && length + 1 < self.cycle.len() {
+                    2
+                } else {
+                    1
+                };
+                // cells it cuts past are left behind as gaps in its body until the
+                // tail catches up, and it can box itself in if it eats too much before
+                // then. so it only cuts across while it's short, has plenty of room
+                // and there's only one apple, since with more it could eat every tick.
+                let is_risky = steps > 1
+                    && (length * 2 >= self.cycle.len()
+                        || room.saturating_sub(steps) * 2 < self.cycle.len()
+                        || state.get_apples().len() > 1);
+                (steps > 0 && steps + spare <= room && !is_risky && !is_blocked(&next))
+                    .then_some((d, steps))
+            })
+            .collect();
+        // cut across as far as it can without going past the apple
+        let shortcut = apple.and_then(|apple| {
+            moves
+                .iter()
+                .filter(|(_, steps)| *steps <= self.count_steps(at, apple))
+                .max_by_key(|(_, steps)| *steps)
+        });
+        // otherwise stick to the cycle, even if that means eating something nasty
+        if let Some((direction, _)) = shortcut.or_else(|| moves.iter().min_by_key(|(_, s)| *s)) {
+            return Some(*direction);
+        }
+        let next = self.cycle[(at + 1) % self.cycle.len()];
+        Direction::ALL
+            .into_iter()
+            .find(|d| state.get_walls().neighbor(&head.get_position(), *d) == Some(next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GameOptions, SlitherResult, Topology};
+
+    fn play_to_the_end(mut state: State) -> SlitherResult {
+        let mut hamiltonian = Hamiltonian::new(state.get_walls()).unwrap();
+        loop {
+            if let Some(direction) = hamiltonian.steer(&state, 0) {
+                state.turn_snek(direction);
+            }
+            let slither_result = state.tick();
+            if state.is_game_over() {
+                return slither_result;
+            }
+        }
+    }
+
+    #[test]
+    fn it_eats_the_world() {
+        for (width, height) in [(4, 4), (6, 5), (5, 8), (8, 8), (13, 10)] {
+            for seed in 0..3 {
+                let state = State::with_seed(width, height, seed);
+                assert_eq!(
+                    play_to_the_end(state),
+                    SlitherResult::AteTheWorld,
+                    "{}x{} with seed {}",
+                    width,
+                    height,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_eats_a_wraparound_world() {
+        let options = GameOptions {
+            topology: Topology::Toroidal,
+            ..GameOptions::default()
+        };
+        let state = State::with_options(6, 7, 4, options);
+        assert_eq!(play_to_the_end(state), SlitherResult::AteTheWorld);
+    }
+
+    #[test]
+    fn it_knows_when_there_is_no_way_round() {
+        let odd = Walls::new(7, 9);
+        assert_eq!(
+            Hamiltonian::new(&odd).unwrap_err(),
+            NoCycle {
+                width: 7,
+                height: 9
+            }
+        );
+        let mut pillar = Walls::new(8, 8);
+        pillar.add_wall(Position::new(3, 3));
+        assert!(Hamiltonian::new(&pillar).is_err());
+        let corridor = Walls::new(10, 3);
+        assert!(Hamiltonian::new(&corridor).is_err());
+    }
+}
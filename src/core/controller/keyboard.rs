@@ -0,0 +1,70 @@
+use crate::core::{Controller, Direction, State, MAX_QUEUED_TURNS};
+
+use crossbeam_channel::{bounded, Receiver, Sender};
+
+/// steers with the keys that were pressed, one turn per tick, so
+/// presses made quicker than the snek moves aren't lost. the front-end
+/// sends each press down the channel it came with, which only has room
+/// for as many turns as the game would queue up, and drops the rest.
+pub struct Keyboard {
+    presses: Receiver<Direction>,
+}
+
+impl Keyboard {
+    pub fn new() -> (Sender<Direction>, Keyboard) {
+        let (sender, presses) = bounded(MAX_QUEUED_TURNS + 1);
+        (sender, Keyboard { presses })
+    }
+}
+
+impl Controller for Keyboard {
+    /// the oldest key pressed that turns the snek the way it's going
+    /// now. presses that would double it back on itself are dropped.
+    fn steer(&mut self, state: &State, player: usize) -> Option<Direction> {
+        let heading = state.get_players()[player]
+            .get_snek()
+            .get_head()
+            .get_direction();
+        self.presses
+            .try_iter()
+            .find(|direction| *direction != heading && !direction.is_on_a_dime(&heading))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_ignores_presses_that_would_turn_back() {
+        let state = State::with_seed(10, 10, 1);
+        let (keys, mut keyboard) = Keyboard::new();
+        keys.send(Direction::Up).unwrap();
+        keys.send(Direction::Left).unwrap();
+        assert_eq!(keyboard.steer(&state, 0), Some(Direction::Up));
+        assert_eq!(keyboard.steer(&state, 0), None);
+    }
+
+    #[test]
+    fn quick_presses_take_a_tick_each() {
+        let mut state = State::with_seed(10, 10, 1);
+        let (keys, mut keyboard) = Keyboard::new();
+        let heading = state.get_direction();
+        keys.send(heading.right()).unwrap();
+        keys.send(heading).unwrap();
+        let first = keyboard.steer(&state, 0).unwrap();
+        assert_eq!(first, heading.right());
+        state.turn_snek(first);
+        state.tick();
+        assert_eq!(keyboard.steer(&state, 0), Some(heading));
+    }
+
+    #[test]
+    fn it_only_holds_so_many_presses() {
+        let (keys, _keyboard) = Keyboard::new();
+        for _ in 0..=MAX_QUEUED_TURNS {
+            keys.try_send(Direction::Up).unwrap();
+        }
+        assert!(keys.try_send(Direction::Up).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+mod autopilot;
+mod greedy;
+mod hamiltonian;
+mod keyboard;
+mod scripted;
+
+pub use autopilot::Autopilot;
+pub use greedy::Greedy;
+pub use hamiltonian::{Hamiltonian, NoCycle};
+pub use keyboard::Keyboard;
+pub use scripted::Scripted;
+
+use crate::core::{Direction, SlitherResult, State, Walls};
+
+use std::str::FromStr;
+
+/// something that steers a snek, whether that's
+/// somebody at a keyboard, a script or a bot
+pub trait Controller: Send + Sync {
+    /// which way `player`'s snek should turn before the next tick,
+    /// or `None` to keep going the way it's going. it's only
+    /// asked while the snek is alive.
+    fn steer(&mut self, state: &State, player: usize) -> Option<Direction>;
+}
+
+/// the bots that can play a game on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bot {
+    /// heads straight for the nearest apple
+    Greedy,
+    /// finds its way to the apple without trapping itself
+    #[default]
+    Autopilot,
+    /// goes round and round the board until it's eaten it all
+    Hamiltonian,
+}
+
+impl Bot {
+    /// a controller for the bot to play on a board with these walls,
+    /// which not every bot can
+    pub fn to_controller(&self, walls: &Walls) -> Result<Box<dyn Controller>, NoCycle> {
+        Ok(match self {
+            Bot::Greedy => Box::new(Greedy),
+            Bot::Autopilot => Box::new(Autopilot::new()),
+            Bot::Hamiltonian => Box::new(Hamiltonian::new(walls)?),
+        })
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            Bot::Greedy => "greedy",
+            Bot::Autopilot => "autopilot",
+            Bot::Hamiltonian => "hamiltonian",
+        }
+    }
+}
+
+impl FromStr for Bot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greedy" => Ok(Bot::Greedy),
+            "autopilot" => Ok(Bot::Autopilot),
+            "hamiltonian" => Ok(Bot::Hamiltonian),
+            _ => Err(format!(
+                "expected `greedy`, `autopilot` or `hamiltonian`, got `{}`",
+                s
+            )),
+        }
+    }
+}
+
+impl State {
+    /// asks each player's controller which way to go, then ticks the
+    /// game. there should be a controller for every player, in order.
+    pub fn tick_with(&mut self, controllers: &mut [Box<dyn Controller>]) -> Vec<SlitherResult> {
+        if controllers.len() != self.get_players().len() {
+            panic!("every player needs a controller");
+        }
+        for (player, controller) in controllers.iter_mut().enumerate() {
+            if !self.get_players()[player].is_alive() {
+                continue;
+            }
+            if let Some(direction) = controller.steer(self, player) {
+                self.turn_player(player, direction);
+            }
+        }
+        self.tick_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::GameOptions;
+
+    #[test]
+    fn humans_and_bots_can_play_together() {
+        let mut state = State::with_options(12, 12, 6, GameOptions::two_player());
+        let (keys, keyboard) = Keyboard::new();
+        let mut controllers: Vec<Box<dyn Controller>> = vec![Box::new(keyboard), Box::new(Greedy)];
+        keys.send(Direction::Down).unwrap();
+        state.tick_with(&mut controllers);
+        assert_eq!(state.get_direction(), Direction::Down);
+        for _ in 0..5 {
+            state.tick_with(&mut controllers);
+        }
+        assert!(state.get_players()[1].is_alive());
+    }
+}
@@ -0,0 +1,55 @@
+use crate::core::{Controller, Direction, State};
+
+use std::collections::VecDeque;
+
+/// steers the same way every time, turning on particular ticks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scripted {
+    /// which tick to turn before, and which way, in order
+    turns: VecDeque<(usize, Direction)>,
+}
+
+impl Scripted {
+    pub fn new(turns: impl IntoIterator<Item = (usize, Direction)>) -> Self {
+        let mut turns: Vec<(usize, Direction)> = turns.into_iter().collect();
+        turns.sort_by_key(|(tick, _)| *tick);
+        Self {
+            turns: turns.into(),
+        }
+    }
+}
+
+impl Controller for Scripted {
+    /// the last turn due by now. turns whose tick went by while the snek
+    /// wasn't being asked, like after it died, are skipped.
+    fn steer(&mut self, state: &State, _player: usize) -> Option<Direction> {
+        let mut direction = None;
+        while let Some((_, turn)) = self
+            .turns
+            .pop_front_if(|(tick, _)| *tick <= state.get_tick())
+        {
+            direction = Some(turn);
+        }
+        direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_turns_on_the_right_ticks() {
+        let mut state = State::with_seed(10, 10, 2);
+        let mut scripted = Scripted::new([(2, Direction::Up), (1, Direction::Down)]);
+        let mut turns = Vec::new();
+        for _ in 0..3 {
+            turns.push(scripted.steer(&state, 0));
+            state.tick();
+        }
+        assert_eq!(
+            turns,
+            vec![None, Some(Direction::Down), Some(Direction::Up)]
+        );
+    }
+}
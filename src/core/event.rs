@@ -0,0 +1,100 @@
+use crate::core::{ActivePowerUp, Apple, DeathCause, Direction};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// something that happened in a game, for anything that wants to
+/// know without picking through every [`SlitherResult`](crate::core::SlitherResult)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// a tick is about to run. `tick` is how many ran before it.
+    TickStarted {
+        tick: usize,
+    },
+    /// a turn was accepted, to happen on the next tick or queued up behind it
+    Turned {
+        player: usize,
+        direction: Direction,
+    },
+    /// a turn that would have doubled the snek back on itself,
+    /// or that came when there were already too many queued up
+    TurnRejected {
+        player: usize,
+        direction: Direction,
+    },
+    AppleEaten {
+        player: usize,
+        apple: Apple,
+    },
+    AppleSpawned {
+        apple: Apple,
+    },
+    SnekDied {
+        player: usize,
+        cause: DeathCause,
+    },
+    AteTheWorld {
+        player: usize,
+    },
+    /// a player's score is now `score`. a rewound game can take points away.
+    ScoreChanged {
+        player: usize,
+        score: usize,
+    },
+    /// a player's power-ups are now `power_ups`, whether one was picked
+    /// up, one wore off, or they just had a tick less left
+    PowerUpsChanged {
+        player: usize,
+        power_ups: Vec<ActivePowerUp>,
+    },
+}
+
+/// whoever's listening to a game's events. they're listening to the game
+/// being played, so copies of it, like the snapshots a peer rewinds to and
+/// the forks bots try moves out on, start off with nobody listening.
+#[derive(Default)]
+pub(crate) struct Subscribers(Vec<Sender<GameEvent>>);
+
+impl Subscribers {
+    pub(crate) fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = unbounded();
+        self.add(sender);
+        receiver
+    }
+
+    pub(crate) fn add(&mut self, subscriber: Sender<GameEvent>) {
+        self.0.push(subscriber);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// tells everyone still listening about `event`, and
+    /// forgets about anyone who's stopped listening
+    pub(crate) fn publish(&mut self, event: GameEvent) {
+        self.0
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for Subscribers {
+    /// two games are the same whoever's listening to them
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} subscribers", self.0.len())
+    }
+}
@@ -0,0 +1,326 @@
+use crate::core::{data_dir, DeathCause, State};
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// how many scores are kept for each board size
+pub const MAX_SCORES_PER_BOARD: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: usize,
+    pub length: usize,
+    pub width: usize,
+    pub height: usize,
+    /// `None` if the snek ate the world
+    pub death_cause: Option<DeathCause>,
+    /// seconds since the unix epoch
+    pub date: u64,
+}
+
+impl HighScore {
+    /// records how a finished game went, dated right now
+    pub fn new(name: &str, state: &State, death_cause: Option<DeathCause>) -> Self {
+        let (width, height) = state.get_walls().get_dimensions();
+        let date = now();
+        Self {
+            name: name.to_string(),
+            score: state.get_score(),
+            length: state.get_snek().count_segments(),
+            width,
+            height,
+            death_cause,
+            date,
+        }
+    }
+
+    pub fn get_board_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn describe_ending(&self) -> &str {
+        match &self.death_cause {
+            Some(death_cause) => death_cause.describe(),
+            None => "it ate the world",
+        }
+    }
+
+    /// the date this score was set on, as `YYYY-MM-DD`
+    pub fn describe_date(&self) -> String {
+        describe_date(self.date)
+    }
+}
+
+/// seconds since the unix epoch
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `date`, in seconds since the unix epoch, as `YYYY-MM-DD`
+pub(crate) fn describe_date(date: u64) -> String {
+    let (year, month, day) = civil_from_days((date / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// converts days since the unix epoch into a (year, month, day) in the
+/// proleptic gregorian calendar. see http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_portion = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_portion + 2) / 5 + 1) as u32;
+    let month = if month_portion < 10 {
+        month_portion + 3
+    } else {
+        month_portion - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    HighestFirst,
+    LowestFirst,
+}
+
+impl SortOrder {
+    pub fn describe(&self) -> &str {
+        match self {
+            SortOrder::HighestFirst => "highest first",
+            SortOrder::LowestFirst => "lowest first",
+        }
+    }
+}
+
+/// the high score table. scores are only comparable between
+/// games played on the same board size, so the best
+/// [`MAX_SCORES_PER_BOARD`] are kept for every size.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// where the high score table lives in the data directory
+    pub fn default_path() -> io::Result<PathBuf> {
+        Ok(data_dir()?.join("high_scores.json"))
+    }
+
+    /// loads the high score table, starting
+    /// a fresh one if the file doesn't exist yet
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match File::open(path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).map_err(io::Error::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self).map_err(io::Error::from)
+    }
+
+    pub fn get_entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// whether a score would make it onto the table for its board size
+    pub fn qualifies(&self, score: usize, board_size: (usize, usize)) -> bool {
+        if score == 0 {
+            return false;
+        }
+        let board = self.sorted(SortOrder::HighestFirst, Some(board_size));
+        board.len() < MAX_SCORES_PER_BOARD || board.last().is_none_or(|h| score > h.score)
+    }
+
+    /// adds a score to the table, returning its rank among
+    /// scores on the same board (starting at 1), or `None`
+    /// if it wasn't good enough to be kept
+    pub fn add(&mut self, high_score: HighScore) -> Option<usize> {
+        let board_size = high_score.get_board_size();
+        if !self.qualifies(high_score.score, board_size) {
+            return None;
+        }
+        self.entries.push(high_score.clone());
+        let mut board = self.sorted(SortOrder::HighestFirst, Some(board_size));
+        let rank = board.iter().position(|h| *h == &high_score).map(|i| i + 1);
+        if board.len() > MAX_SCORES_PER_BOARD {
+            let dropped = board.pop().unwrap().clone();
+            if let Some(i) = self.entries.iter().rposition(|h| h == &dropped) {
+                self.entries.remove(i);
+            }
+        }
+        rank
+    }
+
+    /// the scores on the table, optionally only those set on
+    /// one board size. ties are broken by whoever got there first.
+    pub fn sorted(&self, order: SortOrder, board_size: Option<(usize, usize)>) -> Vec<&HighScore> {
+        let mut entries: Vec<&HighScore> = self
+            .entries
+            .iter()
+            .filter(|h| board_size.is_none_or(|size| h.get_board_size() == size))
+            .collect();
+        entries.sort_by(|a, b| match order {
+            SortOrder::HighestFirst => b.score.cmp(&a.score).then(a.date.cmp(&b.date)),
+            SortOrder::LowestFirst => a.score.cmp(&b.score).then(a.date.cmp(&b.date)),
+        });
+        entries
+    }
+
+    /// every board size with at least one score, smallest first
+    pub fn get_board_sizes(&self) -> Vec<(usize, usize)> {
+        let mut board_sizes: Vec<(usize, usize)> =
+            self.entries.iter().map(|h| h.get_board_size()).collect();
+        board_sizes.sort_by_key(|(width, height)| (width * height, *width));
+        board_sizes.dedup();
+        board_sizes
+    }
+
+    /// the name on the most recently set score,
+    /// handy for pre-filling the name prompt
+    pub fn get_last_name(&self) -> Option<&str> {
+        self.entries
+            .iter()
+            .max_by_key(|h| h.date)
+            .map(|h| h.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(name: &str, score: usize, board_size: (usize, usize), date: u64) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            length: score + 1,
+            width: board_size.0,
+            height: board_size.1,
+            death_cause: Some(DeathCause::Wall),
+            date,
+        }
+    }
+
+    #[test]
+    fn it_sorts_by_score() {
+        let mut high_scores = HighScores::new();
+        high_scores.add(high_score("a", 3, (10, 10), 1));
+        high_scores.add(high_score("b", 7, (10, 10), 2));
+        high_scores.add(high_score("c", 5, (10, 10), 3));
+        let names = |order| {
+            high_scores
+                .sorted(order, None)
+                .iter()
+                .map(|h| h.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(SortOrder::HighestFirst), vec!["b", "c", "a"]);
+        assert_eq!(names(SortOrder::LowestFirst), vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn it_filters_by_board_size() {
+        let mut high_scores = HighScores::new();
+        high_scores.add(high_score("small", 3, (40, 20), 1));
+        high_scores.add(high_score("big", 70, (200, 60), 2));
+        let small = high_scores.sorted(SortOrder::HighestFirst, Some((40, 20)));
+        assert_eq!(small.len(), 1);
+        assert_eq!(small[0].name, "small");
+        assert_eq!(high_scores.get_board_sizes(), vec![(40, 20), (200, 60)]);
+    }
+
+    #[test]
+    fn it_keeps_the_best_scores_per_board() {
+        let mut high_scores = HighScores::new();
+        for score in 1..=MAX_SCORES_PER_BOARD {
+            assert!(high_scores
+                .add(high_score("a", score, (10, 10), score as u64))
+                .is_some());
+        }
+        // a different board has its own table
+        assert_eq!(high_scores.add(high_score("b", 1, (20, 10), 100)), Some(1));
+        // too low to make the table
+        assert!(!high_scores.qualifies(1, (10, 10)));
+        assert_eq!(high_scores.add(high_score("c", 1, (10, 10), 101)), None);
+        // knocks the lowest score off the table
+        assert_eq!(high_scores.add(high_score("d", 5, (10, 10), 102)), Some(7));
+        let board = high_scores.sorted(SortOrder::LowestFirst, Some((10, 10)));
+        assert_eq!(board.len(), MAX_SCORES_PER_BOARD);
+        assert_eq!(board[0].score, 2);
+    }
+
+    #[test]
+    fn it_does_not_keep_zero_scores() {
+        let mut high_scores = HighScores::new();
+        assert_eq!(high_scores.add(high_score("a", 0, (10, 10), 1)), None);
+        assert!(high_scores.get_entries().is_empty());
+    }
+
+    #[test]
+    fn it_remembers_the_last_name() {
+        let mut high_scores = HighScores::new();
+        assert_eq!(high_scores.get_last_name(), None);
+        high_scores.add(high_score("old", 9, (10, 10), 1));
+        high_scores.add(high_score("new", 2, (10, 10), 2));
+        assert_eq!(high_scores.get_last_name(), Some("new"));
+    }
+
+    #[test]
+    fn it_describes_dates() {
+        assert_eq!(
+            high_score("a", 1, (10, 10), 0).describe_date(),
+            "1970-01-01"
+        );
+        assert_eq!(
+            high_score("a", 1, (10, 10), 951_782_400).describe_date(),
+            "2000-02-29"
+        );
+        assert_eq!(
+            high_score("a", 1, (10, 10), 1_792_195_200).describe_date(),
+            "2026-10-17"
+        );
+    }
+
+    #[test]
+    fn it_round_trips_through_a_file() {
+        let mut high_scores = HighScores::new();
+        high_scores.add(high_score("a", 4, (10, 10), 1));
+        let mut ate_the_world = high_score("b", 9, (4, 4), 2);
+        ate_the_world.death_cause = None;
+        high_scores.add(ate_the_world);
+        let path =
+            std::env::temp_dir().join(format!("snek-high-scores-{}.json", std::process::id()));
+        high_scores.save(&path).unwrap();
+        let loaded = HighScores::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, high_scores);
+    }
+
+    #[test]
+    fn it_starts_empty_without_a_file() {
+        let path = std::env::temp_dir().join("snek-high-scores-that-do-not-exist.json");
+        assert!(HighScores::load(path).unwrap().get_entries().is_empty());
+    }
+}
@@ -0,0 +1,515 @@
+use crate::core::{
+    data_dir, Apple, Direction, GameOptions, Position, Snek, State, Topology, Walls,
+};
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// the levels that ship with snek
+const BUNDLED_LEVELS: [&str; 3] = [
+    include_str!("../../levels/pillars.txt"),
+    include_str!("../../levels/corridors.txt"),
+    include_str!("../../levels/donut.txt"),
+];
+
+/// a hand-made board, read from a plain-text map like this one:
+///
+/// ```text
+/// name: tiny box
+/// par: 5
+/// direction: right
+/// length: 2
+/// ---
+/// ######
+/// #S...#
+/// #....#
+/// #..A.#
+/// ######
+/// ```
+///
+/// the optional header holds `key: value` lines and ends at `---`.
+/// it can set the level's `name`, its `par` score, the `direction`
+/// the snek starts heading in, the snek's starting `length` and the
+/// board's `topology` (`bounded` or `wraparound`).
+///
+/// in the map, `#` is a wall and `.` is floor. `S` is the tip of the
+/// snek's tail, which stretches `length` cells towards `direction`,
+/// and each `A` is where an apple grows. the level keeps as many
+/// apples on the board as it starts with, and without any `A`
+/// a single apple grows somewhere random.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    name: String,
+    par: Option<usize>,
+    walls: Walls,
+    snek: Snek,
+    apples: Vec<Apple>,
+}
+
+/// why a level couldn't be loaded. lines and columns start at 1.
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Invalid {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl LevelError {
+    fn invalid(line: usize, column: usize, message: impl Into<String>) -> Self {
+        LevelError::Invalid {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(e) => write!(f, "{}", e),
+            LevelError::Invalid {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<io::Error> for LevelError {
+    fn from(e: io::Error) -> Self {
+        LevelError::Io(e)
+    }
+}
+
+/// where each cell of the map came from in the file
+struct MapLayout {
+    first_line: usize,
+    width: usize,
+}
+
+impl MapLayout {
+    fn error_at(&self, position: &Position, message: impl Into<String>) -> LevelError {
+        let (x, y) = position.get_coordinates();
+        LevelError::invalid(self.first_line + y, x + 1, message)
+    }
+}
+
+impl Level {
+    /// reads a level from the text of a map file
+    pub fn parse(text: &str) -> Result<Level, LevelError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let (header, map_start) = match lines.iter().position(|l| l.trim() == "---") {
+            Some(separator) => (&lines[..separator], separator + 1),
+            None => (&lines[..0], 0),
+        };
+
+        let mut name = "untitled".to_string();
+        let mut par = None;
+        let mut direction = Direction::Right;
+        let mut length = 1;
+        let mut topology = Topology::Bounded;
+        for (i, line) in header.iter().enumerate() {
+            let line_number = i + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once(':').ok_or_else(|| {
+                LevelError::invalid(line_number, 1, "expected a `key: value` header line")
+            })?;
+            let value = value.trim();
+            let value_column = line.len() - line[key.len() + 1..].trim_start().len() + 1;
+            let bad_value = |what: &str| {
+                LevelError::invalid(
+                    line_number,
+                    value_column,
+                    format!("`{}` is not {}", value, what),
+                )
+            };
+            match key.trim() {
+                "name" => name = value.to_string(),
+                "par" => par = Some(value.parse().map_err(|_| bad_value("a score"))?),
+                "direction" => {
+                    direction = match value {
+                        "up" => Direction::Up,
+                        "down" => Direction::Down,
+                        "left" => Direction::Left,
+                        "right" => Direction::Right,
+                        _ => return Err(bad_value("up, down, left or right")),
+                    }
+                }
+                "length" => {
+                    length = match value.parse() {
+                        Ok(length) if length > 0 => length,
+                        _ => return Err(bad_value("a length of at least 1")),
+                    }
+                }
+                "topology" => {
+                    topology = match value {
+                        "bounded" => Topology::Bounded,
+                        "wraparound" => Topology::Toroidal,
+                        _ => return Err(bad_value("bounded or wraparound")),
+                    }
+                }
+                key => {
+                    return Err(LevelError::invalid(
+                        line_number,
+                        1,
+                        format!("unknown header `{}`", key),
+                    ))
+                }
+            }
+        }
+
+        let mut rows: Vec<&str> = lines[map_start..].to_vec();
+        while rows.last().is_some_and(|row| row.trim().is_empty()) {
+            rows.pop();
+        }
+        let layout = MapLayout {
+            first_line: map_start + 1,
+            width: rows.first().map_or(0, |row| row.chars().count()),
+        };
+        let height = rows.len();
+        if layout.width * height < 16 {
+            return Err(LevelError::invalid(
+                layout.first_line,
+                1,
+                "the map must have at least 16 cells",
+            ));
+        }
+
+        let mut walls = Walls::empty(layout.width, height, topology);
+        let mut tail_tip = None;
+        let mut apples = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let row_width = row.chars().count();
+            if row_width != layout.width {
+                return Err(LevelError::invalid(
+                    layout.first_line + y,
+                    row_width.min(layout.width) + 1,
+                    format!(
+                        "every row must be {} cells wide, but this one is {}",
+                        layout.width, row_width
+                    ),
+                ));
+            }
+            for (x, c) in row.chars().enumerate() {
+                let position = Position::new(x, y);
+                match c {
+                    '#' => walls.add_wall(position),
+                    '.' => {}
+                    'S' if tail_tip.is_some() => {
+                        return Err(layout.error_at(&position, "there can only be one snek"))
+                    }
+                    'S' => tail_tip = Some(position),
+                    'A' => apples.push(Apple::new(position)),
+                    c => {
+                        return Err(
+                            layout.error_at(&position, format!("unknown map character `{}`", c))
+                        )
+                    }
+                }
+            }
+        }
+
+        let tail_tip = tail_tip.ok_or_else(|| {
+            LevelError::invalid(layout.first_line, 1, "the map needs an `S` for the snek")
+        })?;
+        let mut body = vec![tail_tip];
+        for _ in 1..length {
+            let last = *body.last().unwrap();
+            let next = last
+                .neighbor(direction)
+                .filter(|p| p.get_coordinates().0 < layout.width)
+                .filter(|p| p.get_coordinates().1 < height)
+                .ok_or_else(|| layout.error_at(&tail_tip, "the snek would stick out of the map"))?;
+            if walls.is_wall(&next) {
+                return Err(layout.error_at(&next, "the snek would start inside a wall"));
+            }
+            body.push(next);
+        }
+        for apple in &apples {
+            if body.contains(&apple.get_position()) {
+                return Err(layout.error_at(
+                    &apple.get_position(),
+                    "the apple can't start under the snek",
+                ));
+            }
+        }
+        let head = *body.last().unwrap();
+        if walls
+            .neighbor(&head, direction)
+            .is_none_or(|next| walls.is_wall(&next))
+        {
+            return Err(layout.error_at(&head, "the snek would start facing a wall"));
+        }
+        if let Some(unreachable) = find_unreachable(&walls, head) {
+            return Err(layout.error_at(
+                &unreachable,
+                "this floor can't be reached from where the snek starts",
+            ));
+        }
+        if apples.is_empty() && walls.get_max_segments() == body.len() {
+            return Err(layout.error_at(&tail_tip, "there's no room left for an apple"));
+        }
+
+        Ok(Level {
+            name,
+            par,
+            walls,
+            snek: Snek::line_snek(tail_tip, direction, length),
+            apples,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Level, LevelError> {
+        Level::parse(&fs::read_to_string(path)?)
+    }
+
+    /// the levels that ship with snek
+    pub fn bundled() -> Vec<Level> {
+        BUNDLED_LEVELS
+            .iter()
+            .map(|text| Level::parse(text).expect("bundled levels are valid"))
+            .collect()
+    }
+
+    /// where players can drop their own `.txt` levels
+    pub fn user_levels_dir() -> io::Result<PathBuf> {
+        let dir = data_dir()?.join("levels");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// every `.txt` file in the user levels directory, sorted by name
+    pub fn find_user_levels() -> io::Result<Vec<PathBuf>> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(Level::user_levels_dir()?)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// the score the level's designer thinks is a good run
+    pub fn get_par(&self) -> Option<usize> {
+        self.par
+    }
+
+    pub fn get_walls(&self) -> &Walls {
+        &self.walls
+    }
+
+    pub fn get_snek(&self) -> &Snek {
+        &self.snek
+    }
+
+    pub fn get_apples(&self) -> &[Apple] {
+        &self.apples
+    }
+
+    /// a fresh game on this level
+    pub fn to_state(&self, seed: u64) -> State {
+        State::from_parts(
+            self.walls.clone(),
+            vec![self.snek.clone()],
+            self.apples.clone(),
+            seed,
+            GameOptions {
+                apples: self.apples.len().max(1),
+                ..GameOptions::default()
+            },
+        )
+    }
+}
+
+/// the first floor cell, reading the map left to right and
+/// top to bottom, that the snek can't get to from `start`
+fn find_unreachable(walls: &Walls, start: Position) -> Option<Position> {
+    let (width, height) = walls.get_dimensions();
+    let index = |p: &Position| p.get_coordinates().0 + p.get_coordinates().1 * width;
+    let mut reached = vec![false; width * height];
+    let mut frontier = VecDeque::from([start]);
+    reached[index(&start)] = true;
+    while let Some(position) = frontier.pop_front() {
+        for direction in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            if let Some(next) = walls.neighbor(&position, direction) {
+                if walls.is_position_inside(&next) && !reached[index(&next)] {
+                    reached[index(&next)] = true;
+                    frontier.push_back(next);
+                }
+            }
+        }
+    }
+    walls
+        .get_free_positions()
+        .into_iter()
+        .find(|p| !reached[index(p)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SlitherResult;
+
+    const TINY_BOX: &str = "name: tiny box
+par: 5
+direction: right
+length: 2
+---
+######
+#S...#
+#....#
+#..A.#
+######
+";
+
+    fn invalid_at(text: &str) -> (usize, usize, String) {
+        match Level::parse(text).unwrap_err() {
+            LevelError::Invalid {
+                line,
+                column,
+                message,
+            } => (line, column, message),
+            LevelError::Io(e) => panic!("unexpected io error: {}", e),
+        }
+    }
+
+    #[test]
+    fn it_parses_a_level() {
+        let level = Level::parse(TINY_BOX).unwrap();
+        assert_eq!(level.get_name(), "tiny box");
+        assert_eq!(level.get_par(), Some(5));
+        assert_eq!(level.get_walls().get_dimensions(), (6, 5));
+        assert!(level.get_walls().is_wall(&Position::new(0, 0)));
+        assert!(!level.get_walls().is_wall(&Position::new(1, 1)));
+        assert_eq!(
+            level.get_snek().get_segment_positions(),
+            vec![Position::new(1, 1), Position::new(2, 1)]
+        );
+        assert_eq!(level.get_apples(), &[Apple::new(Position::new(3, 3))]);
+    }
+
+    #[test]
+    fn it_plays_a_level() {
+        let mut state = Level::parse(TINY_BOX).unwrap().to_state(1);
+        assert_eq!(state.get_direction(), Direction::Right);
+        assert_eq!(state.get_apples(), &[Apple::new(Position::new(3, 3))]);
+        state.turn_snek(Direction::Down);
+        state.tick();
+        state.tick();
+        state.turn_snek(Direction::Right);
+        assert!(matches!(state.tick(), SlitherResult::Grew { .. }));
+        assert_eq!(state.get_score(), 1);
+    }
+
+    #[test]
+    fn it_reads_a_map_without_a_header() {
+        let level = Level::parse("#####\n#S..#\n#...#\n#####\n").unwrap();
+        assert_eq!(level.get_name(), "untitled");
+        assert_eq!(level.get_par(), None);
+        assert!(level.get_apples().is_empty());
+        let state = level.to_state(3);
+        assert!(state
+            .get_walls()
+            .is_position_inside(&state.get_apples()[0].get_position()));
+    }
+
+    #[test]
+    fn it_reads_wraparound_levels() {
+        let level = Level::parse("topology: wraparound\n---\nS...\n....\n..#.\n....\n").unwrap();
+        let state = level.to_state(1);
+        assert_eq!(state.get_options().topology, Topology::Toroidal);
+    }
+
+    #[test]
+    fn it_points_at_unknown_characters() {
+        let (line, column, message) = invalid_at("name: x\n---\n#####\n#S..#\n#.?.#\n#####\n");
+        assert_eq!((line, column), (5, 3));
+        assert!(message.contains('?'));
+    }
+
+    #[test]
+    fn it_points_at_ragged_rows() {
+        let (line, column, _) = invalid_at("#####\n#S..#\n#..#\n#####\n");
+        assert_eq!((line, column), (3, 5));
+    }
+
+    #[test]
+    fn it_points_at_bad_headers() {
+        assert_eq!(
+            invalid_at("length: 0\n---\n#####\n#S..#\n#...#\n#####\n").0,
+            1
+        );
+        let (line, column, _) = invalid_at("name: x\ndirection:  sideways\n---\n#####\n#S..#\n");
+        assert_eq!((line, column), (2, 13));
+        assert_eq!(invalid_at("colour: red\n---\n#####\n#S..#\n").0, 1);
+    }
+
+    #[test]
+    fn it_needs_exactly_one_snek() {
+        assert_eq!(invalid_at("#####\n#...#\n#...#\n#####\n").0, 1);
+        let (line, column, _) = invalid_at("#####\n#S..#\n#..S#\n#####\n");
+        assert_eq!((line, column), (3, 4));
+    }
+
+    #[test]
+    fn it_keeps_every_apple_on_the_map() {
+        let level = Level::parse("#####\n#S.A#\n#A..#\n#####\n").unwrap();
+        assert_eq!(level.get_apples().len(), 2);
+        let mut state = level.to_state(1);
+        assert_eq!(state.get_options().apples, 2);
+        assert!(matches!(state.tick(), SlitherResult::Slithered { .. }));
+        assert!(matches!(state.tick(), SlitherResult::Grew { .. }));
+        assert_eq!(state.get_apples().len(), 2);
+    }
+
+    #[test]
+    fn it_rejects_starts_that_overlap_walls() {
+        let (line, column, message) =
+            invalid_at("length: 3\n---\n######\n#S.#.#\n#....#\n######\n");
+        assert_eq!((line, column), (4, 4));
+        assert!(message.contains("wall"));
+        let (line, column, _) = invalid_at("length: 3\n---\n#####\n#...S\n#....\n#####\n");
+        assert_eq!((line, column), (4, 5));
+    }
+
+    #[test]
+    fn it_rejects_unreachable_floor() {
+        let (line, column, message) = invalid_at("#######\n#S.#..#\n#..#..#\n#######\n");
+        assert_eq!((line, column), (2, 5));
+        assert!(message.contains("reached"));
+    }
+
+    #[test]
+    fn it_rejects_tiny_maps() {
+        assert_eq!(invalid_at("###\n#S#\n###\n").0, 1);
+    }
+
+    #[test]
+    fn bundled_levels_are_valid() {
+        for text in BUNDLED_LEVELS {
+            Level::parse(text).unwrap();
+        }
+        assert_eq!(Level::bundled().len(), BUNDLED_LEVELS.len());
+    }
+}
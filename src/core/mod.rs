@@ -1,13 +1,51 @@
 mod apple;
+mod clock;
+mod controller;
+mod event;
+mod high_scores;
+mod level;
+mod occupancy;
+mod options;
+mod player;
 mod position;
+mod power_up;
+mod replay;
 mod scoreboard;
+mod scoring;
+mod simulation;
+mod snapshot;
 mod snek;
+mod speed;
 mod state;
+mod stats;
+mod storage;
+mod topology;
 mod walls;
 
-pub use apple::Apple;
+pub use apple::{
+    Apple, AppleKind, GOLDEN_APPLE_POINTS, POISON_APPLE_SEGMENTS, TIMED_APPLE_POINTS,
+    TIMED_APPLE_TICKS,
+};
+pub use clock::{Clock, MAX_FRAME_TIME};
+pub use controller::{
+    Autopilot, Bot, Controller, Greedy, Hamiltonian, Keyboard, NoCycle, Scripted,
+};
+pub use event::GameEvent;
+pub use high_scores::{HighScore, HighScores, SortOrder, MAX_SCORES_PER_BOARD};
+pub use level::{Level, LevelError};
+pub use options::{GameOptions, HeadOnRule};
+pub use player::{Player, MAX_QUEUED_TURNS};
 pub use position::Position;
-pub use scoreboard::Scoreboard;
+pub use power_up::{ActivePowerUp, PowerUp, PowerUpKind, POWER_UP_CHANCE, POWER_UP_TICKS_ON_BOARD};
+pub use replay::{Divergence, Replay, ReplayOutcome, ReplayTurn, REPLAY_VERSION};
+pub use scoreboard::{Breakdown, Scoreboard};
+pub use scoring::Scoring;
+pub use simulation::{simulate, SimulationOptions, SimulationStats};
+pub use snapshot::Snapshot;
 pub use snek::{DeathCause, Direction, Segment, SlitherAction, SlitherResult, Snek};
-pub use state::State;
+pub use speed::Speed;
+pub use state::{State, MIN_BOARD_CELLS, MIN_BOARD_SIDE, SAVE_VERSION};
+pub use stats::{Achievement, Stats, StatsTracker, LIFETIME_APPLES, STRAIGHT_APPLES};
+pub use storage::data_dir;
+pub use topology::Topology;
 pub use walls::Walls;
@@ -0,0 +1,261 @@
+use crate::core::{Apple, Player, Position, PowerUp, Snek, Walls};
+
+use std::fmt;
+
+/// what's in every cell of the board, kept up to date as the game is
+/// played, so finding out whether a cell is taken or where the empty
+/// ones are doesn't mean going through every wall, apple and snek
+#[derive(Clone, PartialEq)]
+pub(crate) struct Occupancy {
+    width: usize,
+    /// how many things are in each cell: walls, apples, power-ups and living sneks
+    counts: Vec<u32>,
+    /// how many of each player's segments are in each cell. a
+    /// ghost can slither over itself, so there can be more than one.
+    sneks: Vec<Vec<u32>>,
+    /// a fenwick tree over which cells are empty, so the nth empty
+    /// cell can be found without counting every cell before it
+    empty: Vec<usize>,
+    empty_count: usize,
+    /// how many cells aren't walls
+    free_count: usize,
+}
+
+impl Occupancy {
+    /// works out what's in every cell from scratch. dead sneks don't take up any room.
+    pub(crate) fn new(
+        walls: &Walls,
+        players: &[Player],
+        apples: &[Apple],
+        power_ups: &[PowerUp],
+    ) -> Self {
+        let (width, height) = walls.get_dimensions();
+        let counts: Vec<u32> = (0..width * height)
+            .map(|i| walls.is_wall(&Position::new(i % width, i / width)) as u32)
+            .collect();
+        let free_count = counts.iter().filter(|count| **count == 0).count();
+        let mut occupancy = Self {
+            width,
+            counts,
+            sneks: vec![vec![0; width * height]; players.len()],
+            empty: Vec::new(),
+            empty_count: 0,
+            free_count,
+        };
+        for (player, p) in players.iter().enumerate().filter(|(_, p)| p.is_alive()) {
+            for segment in p.get_snek().iter_segments() {
+                let i = occupancy.get_index(&segment.get_position());
+                occupancy.sneks[player][i] += 1;
+                occupancy.counts[i] += 1;
+            }
+        }
+        let items = apples.iter().map(|a| a.get_position());
+        for position in items.chain(power_ups.iter().map(|p| p.get_position())) {
+            let i = occupancy.get_index(&position);
+            occupancy.counts[i] += 1;
+        }
+        occupancy.count_empty_cells();
+        occupancy
+    }
+
+    /// builds the fenwick tree over the empty cells in one go
+    fn count_empty_cells(&mut self) {
+        let len = self.counts.len();
+        self.empty = vec![0; len + 1];
+        for i in 1..=len {
+            self.empty[i] += (self.counts[i - 1] == 0) as usize;
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= len {
+                self.empty[parent] += self.empty[i];
+            }
+        }
+        self.empty_count = self.counts.iter().filter(|count| **count == 0).count();
+    }
+
+    /// puts an apple or power-up in a cell
+    pub(crate) fn add(&mut self, position: Position) {
+        let i = self.get_index(&position);
+        self.counts[i] += 1;
+        if self.counts[i] == 1 {
+            self.update_empty(i, false);
+        }
+    }
+
+    /// takes an apple or power-up out of a cell
+    pub(crate) fn remove(&mut self, position: Position) {
+        let i = self.get_index(&position);
+        self.counts[i] -= 1;
+        if self.counts[i] == 0 {
+            self.update_empty(i, true);
+        }
+    }
+
+    /// makes room for another player's snek
+    pub(crate) fn add_player(&mut self) {
+        self.sneks.push(vec![0; self.counts.len()]);
+    }
+
+    pub(crate) fn add_segment(&mut self, player: usize, position: Position) {
+        let i = self.get_index(&position);
+        self.sneks[player][i] += 1;
+        self.add(position);
+    }
+
+    pub(crate) fn remove_segment(&mut self, player: usize, position: Position) {
+        let i = self.get_index(&position);
+        self.sneks[player][i] -= 1;
+        self.remove(position);
+    }
+
+    pub(crate) fn add_snek(&mut self, player: usize, snek: &Snek) {
+        for segment in snek.iter_segments() {
+            self.add_segment(player, segment.get_position());
+        }
+    }
+
+    /// takes a snek off the board, like when it dies
+    pub(crate) fn remove_snek(&mut self, player: usize, snek: &Snek) {
+        for segment in snek.iter_segments() {
+            self.remove_segment(player, segment.get_position());
+        }
+    }
+
+    /// whether there's nothing at all in a cell. anything off the board isn't empty.
+    pub(crate) fn is_empty(&self, position: &Position) -> bool {
+        self.find_index(position)
+            .is_some_and(|i| self.counts[i] == 0)
+    }
+
+    /// how many of `player`'s segments are at `position`
+    pub(crate) fn count_segments(&self, player: usize, position: &Position) -> usize {
+        self.find_index(position)
+            .map_or(0, |i| self.sneks[player][i] as usize)
+    }
+
+    pub(crate) fn count_empty(&self) -> usize {
+        self.empty_count
+    }
+
+    /// how long a snek could get if it filled every cell that isn't a wall
+    pub(crate) fn get_max_segments(&self) -> usize {
+        self.free_count
+    }
+
+    /// the `n`th empty cell, going across the board a row at a time
+    /// from the top left, the same order as [`Occupancy::get_empty_positions`]
+    pub(crate) fn get_empty_position(&self, n: usize) -> Position {
+        if n >= self.empty_count {
+            panic!("there are only {} empty cells", self.empty_count);
+        }
+        let len = self.counts.len();
+        let (mut i, mut left) = (0, n);
+        let mut step = 1 << len.ilog2();
+        while step > 0 {
+            if i + step <= len && self.empty[i + step] <= left {
+                i += step;
+                left -= self.empty[i];
+            }
+            step >>= 1;
+        }
+        self.get_position(i)
+    }
+
+    /// every empty cell, going across the board a row at a time from the top left
+    pub(crate) fn get_empty_positions(&self) -> Vec<Position> {
+        (0..self.counts.len())
+            .filter(|i| self.counts[*i] == 0)
+            .map(|i| self.get_position(i))
+            .collect()
+    }
+
+    fn update_empty(&mut self, i: usize, empty: bool) {
+        let mut node = i + 1;
+        while node < self.empty.len() {
+            match empty {
+                true => self.empty[node] += 1,
+                false => self.empty[node] -= 1,
+            }
+            node += node & node.wrapping_neg();
+        }
+        match empty {
+            true => self.empty_count += 1,
+            false => self.empty_count -= 1,
+        }
+    }
+
+    fn find_index(&self, position: &Position) -> Option<usize> {
+        let (x, y) = position.get_coordinates();
+        (x < self.width && x + y * self.width < self.counts.len()).then(|| x + y * self.width)
+    }
+
+    fn get_index(&self, position: &Position) -> usize {
+        self.find_index(position)
+            .unwrap_or_else(|| panic!("{:?} is off the board", position))
+    }
+
+    fn get_position(&self, i: usize) -> Position {
+        Position::new(i % self.width, i / self.width)
+    }
+}
+
+impl fmt::Debug for Occupancy {
+    /// the whole board would be a lot to print every time a game is logged
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} empty cells", self.empty_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Direction;
+
+    fn occupancy(walls: &Walls, sneks: Vec<Snek>, apples: &[Apple]) -> Occupancy {
+        let players: Vec<Player> = sneks.into_iter().map(Player::new).collect();
+        Occupancy::new(walls, &players, apples, &[])
+    }
+
+    #[test]
+    fn it_knows_whats_in_every_cell() {
+        let walls = Walls::new(6, 6);
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 3);
+        let apples = [Apple::new(Position::new(4, 4))];
+        let occupancy = occupancy(&walls, vec![snek], &apples);
+        assert!(!occupancy.is_empty(&Position::new(0, 0)));
+        assert!(!occupancy.is_empty(&Position::new(2, 1)));
+        assert!(!occupancy.is_empty(&Position::new(4, 4)));
+        assert!(!occupancy.is_empty(&Position::new(6, 1)));
+        assert!(occupancy.is_empty(&Position::new(1, 2)));
+        assert_eq!(occupancy.count_segments(0, &Position::new(3, 1)), 1);
+        assert_eq!(occupancy.count_segments(0, &Position::new(4, 4)), 0);
+        assert_eq!(occupancy.get_max_segments(), 16);
+        assert_eq!(occupancy.count_empty(), 16 - 3 - 1);
+    }
+
+    #[test]
+    fn it_finds_the_nth_empty_cell_in_order() {
+        let walls = Walls::new(7, 5);
+        let snek = Snek::line_snek(Position::new(1, 2), Direction::Right, 4);
+        let mut occupancy = occupancy(&walls, vec![snek], &[]);
+        occupancy.add(Position::new(3, 1));
+        occupancy.remove_segment(0, Position::new(2, 2));
+        let positions = occupancy.get_empty_positions();
+        assert_eq!(positions.len(), occupancy.count_empty());
+        for (n, position) in positions.into_iter().enumerate() {
+            assert_eq!(occupancy.get_empty_position(n), position);
+        }
+    }
+
+    #[test]
+    fn a_snek_can_be_in_a_cell_more_than_once() {
+        let walls = Walls::new(6, 6);
+        let snek = Snek::baby_snek(Position::new(2, 2), Direction::Up);
+        let mut occupancy = occupancy(&walls, vec![snek.clone()], &[]);
+        occupancy.add_segment(0, Position::new(2, 2));
+        assert_eq!(occupancy.count_segments(0, &Position::new(2, 2)), 2);
+        occupancy.remove_snek(0, &snek);
+        assert!(!occupancy.is_empty(&Position::new(2, 2)));
+        occupancy.remove_segment(0, Position::new(2, 2));
+        assert!(occupancy.is_empty(&Position::new(2, 2)));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::core::{Scoring, Speed, Topology};
+
+use serde::{Deserialize, Serialize};
+
+/// the rules a game is played by. everything that makes one
+/// game mode different from another lives here, so that
+/// replays and saved games bring their rules along with them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameOptions {
+    pub topology: Topology,
+    /// how many apples are on the board at once
+    pub apples: usize,
+    /// whether golden, poison and timed apples grow as well as plain ones
+    pub apple_varieties: bool,
+    /// whether power-ups show up on the board
+    pub power_ups: bool,
+    /// how many sneks are on the board
+    pub players: usize,
+    /// what happens when two sneks meet head-on
+    pub head_on: HeadOnRule,
+    /// how fast the game ticks
+    pub speed: Speed,
+    /// what the sneks get points for
+    pub scoring: Scoring,
+}
+
+/// what happens when two sneks slither into the same cell at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HeadOnRule {
+    /// neither snek survives
+    BothDie,
+    /// the longer snek survives. if they're as long as each other, neither does.
+    #[default]
+    LongerWins,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            topology: Topology::default(),
+            apples: 1,
+            apple_varieties: false,
+            power_ups: false,
+            players: 1,
+            head_on: HeadOnRule::default(),
+            speed: Speed::default(),
+            scoring: Scoring::default(),
+        }
+    }
+}
+
+impl GameOptions {
+    /// a board whose edges wrap around instead of being walled off
+    pub fn wraparound() -> Self {
+        Self {
+            topology: Topology::Toroidal,
+            ..Self::default()
+        }
+    }
+
+    /// a board with apples everywhere
+    pub fn feast() -> Self {
+        Self {
+            apples: 8,
+            ..Self::default()
+        }
+    }
+
+    /// a few apples at once, and not all of them are good for you
+    pub fn orchard() -> Self {
+        Self {
+            apples: 3,
+            apple_varieties: true,
+            ..Self::default()
+        }
+    }
+
+    /// power-ups that bend the rules for a little while
+    pub fn power_ups() -> Self {
+        Self {
+            power_ups: true,
+            ..Self::default()
+        }
+    }
+
+    /// a game that gets faster and faster the more the snek eats
+    pub fn speedy() -> Self {
+        Self {
+            speed: Speed::progressive(),
+            ..Self::default()
+        }
+    }
+
+    /// a game that speeds up, where eating apples back to back
+    /// is worth more and going nowhere costs points
+    pub fn arcade() -> Self {
+        Self {
+            speed: Speed::progressive(),
+            scoring: Scoring::arcade(),
+            ..Self::default()
+        }
+    }
+
+    /// two sneks on the same board, with an apple each
+    pub fn two_player() -> Self {
+        Self {
+            apples: 2,
+            players: 2,
+            ..Self::default()
+        }
+    }
+}
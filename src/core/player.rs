@@ -0,0 +1,149 @@
+use crate::core::{ActivePowerUp, DeathCause, Direction, PowerUpKind, Scoreboard, Snek};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+/// how many turns can wait behind the one the snek takes on the next tick.
+/// any more than this and the oldest ones would be stale by the time they happened.
+pub const MAX_QUEUED_TURNS: usize = 3;
+
+/// one snek in a game, along with everything
+/// that belongs to whoever is steering it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Player {
+    snek: Snek,
+    /// the direction the snek will slither on the next tick
+    direction: Direction,
+    /// turns made too quickly to happen on the next tick, which
+    /// happen one per tick after it instead, oldest first
+    #[serde(default)]
+    queued_turns: VecDeque<Direction>,
+    scoreboard: Scoreboard,
+    /// power-ups this snek has picked up that haven't worn off yet
+    #[serde(default)]
+    active_power_ups: Vec<ActivePowerUp>,
+    #[serde(default)]
+    death_cause: Option<DeathCause>,
+}
+
+impl Player {
+    /// a player whose snek heads whichever way its head points
+    pub fn new(snek: Snek) -> Self {
+        Self::with_parts(snek, None, Scoreboard::new(), Vec::new())
+    }
+
+    pub(crate) fn with_parts(
+        snek: Snek,
+        direction: Option<Direction>,
+        scoreboard: Scoreboard,
+        active_power_ups: Vec<ActivePowerUp>,
+    ) -> Self {
+        let direction = direction.unwrap_or_else(|| snek.get_head().get_direction());
+        Self {
+            snek,
+            direction,
+            queued_turns: VecDeque::new(),
+            scoreboard,
+            active_power_ups,
+            death_cause: None,
+        }
+    }
+
+    pub fn get_snek(&self) -> &Snek {
+        &self.snek
+    }
+
+    pub(crate) fn get_snek_mut(&mut self) -> &mut Snek {
+        &mut self.snek
+    }
+
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    /// the turns waiting to happen after the next tick, oldest first
+    pub fn get_queued_turns(&self) -> &VecDeque<Direction> {
+        &self.queued_turns
+    }
+
+    /// turns the snek on the next tick, or on the tick after the last
+    /// queued turn if it's already turning. a turn that would double the
+    /// snek back on whichever way it'll be going by then is ignored, and
+    /// so is one made when the queue is full.
+    pub(crate) fn turn(&mut self, direction: Direction) -> bool {
+        let heading = self.snek.get_head().get_direction();
+        if self.direction == heading && self.queued_turns.is_empty() {
+            if direction.is_on_a_dime(&heading) {
+                return false;
+            }
+            self.direction = direction;
+            return true;
+        }
+        let last = self.queued_turns.back().copied().unwrap_or(self.direction);
+        if direction == last {
+            return true;
+        }
+        if direction.is_on_a_dime(&last) || self.queued_turns.len() >= MAX_QUEUED_TURNS {
+            return false;
+        }
+        self.queued_turns.push_back(direction);
+        true
+    }
+
+    /// lines up the next queued turn once a tick is over. it's checked
+    /// again against the way the snek actually went, in case a shield
+    /// stopped it from turning.
+    pub(crate) fn take_queued_turn(&mut self) {
+        if let Some(direction) = self.queued_turns.pop_front() {
+            if !direction.is_on_a_dime(&self.snek.get_head().get_direction()) {
+                self.direction = direction;
+            }
+        }
+    }
+
+    pub fn get_score(&self) -> usize {
+        self.scoreboard.get_score()
+    }
+
+    /// the score, and where it came from
+    pub fn get_scoreboard(&self) -> &Scoreboard {
+        &self.scoreboard
+    }
+
+    pub(crate) fn get_scoreboard_mut(&mut self) -> &mut Scoreboard {
+        &mut self.scoreboard
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.snek.is_alive()
+    }
+
+    /// why the snek died, if it has
+    pub fn get_death_cause(&self) -> Option<DeathCause> {
+        self.death_cause
+    }
+
+    pub(crate) fn kill(&mut self, death_cause: DeathCause) {
+        self.snek.kill();
+        self.death_cause = Some(death_cause);
+    }
+
+    /// the power-ups this snek has picked up, and how long each has left
+    pub fn get_active_power_ups(&self) -> &[ActivePowerUp] {
+        &self.active_power_ups
+    }
+
+    pub(crate) fn get_active_power_ups_mut(&mut self) -> &mut Vec<ActivePowerUp> {
+        &mut self.active_power_ups
+    }
+
+    pub fn has_power_up(&self, kind: PowerUpKind) -> bool {
+        self.active_power_ups.iter().any(|p| p.get_kind() == kind)
+    }
+}
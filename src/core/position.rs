@@ -1,6 +1,8 @@
 use crate::core::Direction;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
 pub struct Position {
     x: usize,
     y: usize,
@@ -36,6 +38,18 @@ impl Position {
         }
     }
 
+    /// gets the neighboring position on a board
+    /// of `width` x `height` whose edges wrap around
+    /// to the opposite side
+    pub fn wrapping_neighbor(&self, direction: Direction, width: usize, height: usize) -> Position {
+        match direction {
+            Direction::Up => Position::new(self.x, (self.y + height - 1) % height),
+            Direction::Down => Position::new(self.x, (self.y + 1) % height),
+            Direction::Left => Position::new((self.x + width - 1) % width, self.y),
+            Direction::Right => Position::new((self.x + 1) % width, self.y),
+        }
+    }
+
     pub fn set(&mut self, position: Position) {
         *self = position;
     }
@@ -64,6 +78,33 @@ mod tests {
         assert!(position.neighbor(Direction::Left).is_none());
     }
 
+    #[test]
+    fn it_wraps_around_every_edge() {
+        let top_left = Position::new(0, 0);
+        assert_eq!(
+            top_left.wrapping_neighbor(Direction::Up, 10, 5),
+            Position::new(0, 4)
+        );
+        assert_eq!(
+            top_left.wrapping_neighbor(Direction::Left, 10, 5),
+            Position::new(9, 0)
+        );
+        let bottom_right = Position::new(9, 4);
+        assert_eq!(
+            bottom_right.wrapping_neighbor(Direction::Down, 10, 5),
+            Position::new(9, 0)
+        );
+        assert_eq!(
+            bottom_right.wrapping_neighbor(Direction::Right, 10, 5),
+            Position::new(0, 4)
+        );
+        let middle = Position::new(5, 2);
+        assert_eq!(
+            middle.wrapping_neighbor(Direction::Right, 10, 5),
+            middle.neighbor(Direction::Right).unwrap()
+        );
+    }
+
     #[test]
     fn it_can_nudge_left() {
         let mut old_position = Position::new(10, 10);
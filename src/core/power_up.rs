@@ -0,0 +1,133 @@
+use crate::core::Position;
+
+use serde::{Deserialize, Serialize};
+
+/// how many ticks a power-up waits on the board to be picked up
+pub const POWER_UP_TICKS_ON_BOARD: usize = 50;
+/// one in this many ticks grows a power-up, if there isn't one already
+pub const POWER_UP_CHANCE: u32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    /// the snek can slither through its own tail
+    Ghost,
+    /// the game slows down
+    SlowMotion,
+    /// the game speeds up
+    SpeedBurst,
+    /// the next wall the snek runs into stops it instead of killing it
+    Shield,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::Ghost,
+        PowerUpKind::SlowMotion,
+        PowerUpKind::SpeedBurst,
+        PowerUpKind::Shield,
+    ];
+
+    /// how many ticks the power-up lasts once it's picked up
+    pub fn get_duration(&self) -> usize {
+        match self {
+            PowerUpKind::Ghost => 30,
+            PowerUpKind::SlowMotion => 30,
+            PowerUpKind::SpeedBurst => 40,
+            PowerUpKind::Shield => 100,
+        }
+    }
+
+    /// how much faster than usual the game ticks while the power-up lasts
+    pub fn get_speed_factor(&self) -> f64 {
+        match self {
+            PowerUpKind::SlowMotion => 0.5,
+            PowerUpKind::SpeedBurst => 2.0,
+            PowerUpKind::Ghost | PowerUpKind::Shield => 1.0,
+        }
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            PowerUpKind::Ghost => "ghost",
+            PowerUpKind::SlowMotion => "ssslow motion",
+            PowerUpKind::SpeedBurst => "ssspeed",
+            PowerUpKind::Shield => "ssshield",
+        }
+    }
+}
+
+/// a power-up waiting on the board to be picked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PowerUp {
+    position: Position,
+    kind: PowerUpKind,
+    /// the tick it disappears on if nobody picks it up
+    expires_at: usize,
+}
+
+impl PowerUp {
+    pub fn new(position: Position, kind: PowerUpKind, expires_at: usize) -> Self {
+        Self {
+            position,
+            kind,
+            expires_at,
+        }
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.position
+    }
+
+    pub fn get_kind(&self) -> PowerUpKind {
+        self.kind
+    }
+
+    pub fn has_expired(&self, tick: usize) -> bool {
+        tick >= self.expires_at
+    }
+}
+
+/// a power-up the snek picked up, and how long it has left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ActivePowerUp {
+    kind: PowerUpKind,
+    ticks_left: usize,
+}
+
+impl ActivePowerUp {
+    pub fn new(kind: PowerUpKind) -> Self {
+        Self {
+            kind,
+            ticks_left: kind.get_duration(),
+        }
+    }
+
+    pub fn get_kind(&self) -> PowerUpKind {
+        self.kind
+    }
+
+    pub fn get_ticks_left(&self) -> usize {
+        self.ticks_left
+    }
+
+    /// counts down a tick, returning whether there's any time left
+    pub(crate) fn wear_off(&mut self) -> bool {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+        self.ticks_left > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_ups_wear_off() {
+        let mut power_up = ActivePowerUp::new(PowerUpKind::Ghost);
+        for _ in 1..PowerUpKind::Ghost.get_duration() {
+            assert!(power_up.wear_off());
+        }
+        assert!(!power_up.wear_off());
+        assert_eq!(power_up.get_ticks_left(), 0);
+    }
+}
@@ -0,0 +1,472 @@
+use crate::core::{
+    data_dir, AppleKind, DeathCause, Direction, GameOptions, Position, SlitherResult, State,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// the replay file format version written by this build.
+/// bump this whenever the layout of [`Replay`] changes
+/// in a way older builds can't read, or the rules change
+/// so an older game wouldn't play out the same.
+/// version 4 let sneks slither into the tip of their own tail.
+pub const REPLAY_VERSION: u32 = 4;
+
+/// everything needed to re-drive a fresh [`State`]
+/// through the exact same game: the seed, the board size,
+/// every call to [`State::turn_player`] and what each tick did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    seed: u64,
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    options: GameOptions,
+    /// the game as it was before the first tick, so that
+    /// custom boards play back exactly. version 1 replays
+    /// don't have one and are rebuilt from the seed instead.
+    #[serde(default)]
+    start: Option<Box<State>>,
+    turns: Vec<ReplayTurn>,
+    /// what each tick did to player one's snek
+    outcomes: Vec<ReplayOutcome>,
+    /// what each tick did to everyone else's, in games against each other
+    #[serde(default)]
+    rival_outcomes: Vec<Vec<ReplayOutcome>>,
+}
+
+/// a call to [`State::turn_player`] made before tick number `tick` ran
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayTurn {
+    pub tick: usize,
+    /// which player turned. replays from before two-player games are all player one.
+    #[serde(default)]
+    pub player: usize,
+    pub direction: Direction,
+}
+
+/// a compact record of a [`SlitherResult`],
+/// used to detect when playback stops matching the recording
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplayOutcome {
+    Slithered {
+        direction: Direction,
+        head: Position,
+    },
+    Grew {
+        direction: Direction,
+        head: Position,
+        #[serde(default)]
+        eaten: AppleKind,
+    },
+    Shrank {
+        direction: Direction,
+        head: Position,
+    },
+    Shielded {
+        direction: Direction,
+    },
+    Died(DeathCause),
+    AteTheWorld,
+}
+
+impl ReplayOutcome {
+    pub fn describe(&self) -> String {
+        match self {
+            ReplayOutcome::Slithered { direction, head } => {
+                format!("slither {} to {:?}", direction.describe(), head)
+            }
+            ReplayOutcome::Grew {
+                direction,
+                head,
+                eaten,
+            } => {
+                format!(
+                    "grow {} to {:?} eating a {}",
+                    direction.describe(),
+                    head,
+                    eaten.describe()
+                )
+            }
+            ReplayOutcome::Shrank { direction, head } => {
+                format!("shrink {} to {:?}", direction.describe(), head)
+            }
+            ReplayOutcome::Shielded { direction } => {
+                format!("bounce off a wall going {}", direction.describe())
+            }
+            ReplayOutcome::Died(death_cause) => {
+                format!("die because {}", death_cause.describe())
+            }
+            ReplayOutcome::AteTheWorld => "eat the world".to_string(),
+        }
+    }
+}
+
+impl From<&SlitherResult> for ReplayOutcome {
+    fn from(slither_result: &SlitherResult) -> Self {
+        match slither_result {
+            SlitherResult::Died(death_cause) => ReplayOutcome::Died(*death_cause),
+            SlitherResult::AteTheWorld => ReplayOutcome::AteTheWorld,
+            SlitherResult::Shielded { direction } => ReplayOutcome::Shielded {
+                direction: *direction,
+            },
+            SlitherResult::Grew {
+                direction,
+                segments,
+                slime_trail: _,
+                eaten,
+            } => ReplayOutcome::Grew {
+                direction: *direction,
+                head: segments.last().unwrap().get_position(),
+                eaten: *eaten,
+            },
+            SlitherResult::Shrank {
+                direction,
+                segments,
+                slime_trail: _,
+            } => ReplayOutcome::Shrank {
+                direction: *direction,
+                head: segments.last().unwrap().get_position(),
+            },
+            SlitherResult::Slithered {
+                direction,
+                segments,
+                slime_trail: _,
+            } => ReplayOutcome::Slithered {
+                direction: *direction,
+                head: segments.last().unwrap().get_position(),
+            },
+        }
+    }
+}
+
+/// the first tick where playback stopped matching the recording.
+/// `None` means the game had already ended on that side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    pub tick: usize,
+    pub player: usize,
+    pub expected: Option<ReplayOutcome>,
+    pub actual: Option<ReplayOutcome>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |outcome: &Option<ReplayOutcome>| match outcome {
+            Some(outcome) => outcome.describe(),
+            None => "already be over".to_string(),
+        };
+        let snek = match self.player {
+            0 => "the snek".to_string(),
+            player => format!("player {}'s snek", player + 1),
+        };
+        write!(
+            f,
+            "replay diverged at tick {}: expected {} to {} but it was going to {}",
+            self.tick,
+            snek,
+            describe(&self.expected),
+            describe(&self.actual)
+        )
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+impl Replay {
+    /// starts a replay of `state`, which hasn't ticked yet
+    pub fn new(state: &State) -> Self {
+        let (width, height) = state.get_walls().get_dimensions();
+        let mut start = state.clone();
+        start.stop_recording();
+        Self {
+            version: REPLAY_VERSION,
+            seed: state.get_seed(),
+            width,
+            height,
+            options: state.get_options(),
+            start: Some(Box::new(start)),
+            turns: Vec::new(),
+            outcomes: Vec::new(),
+            rival_outcomes: Vec::new(),
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn get_options(&self) -> GameOptions {
+        self.options
+    }
+
+    pub fn get_turns(&self) -> &[ReplayTurn] {
+        &self.turns
+    }
+
+    pub fn get_outcomes(&self) -> &[ReplayOutcome] {
+        &self.outcomes
+    }
+
+    pub fn count_ticks(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// what each tick did to every player's snek but player one's
+    pub fn get_rival_outcomes(&self) -> &[Vec<ReplayOutcome>] {
+        &self.rival_outcomes
+    }
+
+    pub(crate) fn record_turn(&mut self, tick: usize, player: usize, direction: Direction) {
+        self.turns.push(ReplayTurn {
+            tick,
+            player,
+            direction,
+        });
+    }
+
+    /// records what one tick did to every player, player one first
+    pub(crate) fn record_outcomes(&mut self, slither_results: &[SlitherResult]) {
+        self.outcomes.push(ReplayOutcome::from(&slither_results[0]));
+        if slither_results.len() > 1 {
+            self.rival_outcomes.push(
+                slither_results[1..]
+                    .iter()
+                    .map(ReplayOutcome::from)
+                    .collect(),
+            );
+        }
+    }
+
+    /// re-drives a fresh [`State`] through every recorded tick,
+    /// returning each of player one's [`SlitherResult`]s in order,
+    /// or the first tick where the game played out differently
+    pub fn play(&self) -> Result<Vec<SlitherResult>, Divergence> {
+        let mut state = match &self.start {
+            Some(start) => *start.clone(),
+            None => State::with_options(self.width, self.height, self.seed, self.options),
+        };
+        let mut turns = self.turns.iter().peekable();
+        let mut results = Vec::with_capacity(self.outcomes.len());
+        for (tick, expected) in self.outcomes.iter().enumerate() {
+            if state.is_game_over() {
+                return Err(Divergence {
+                    tick,
+                    player: 0,
+                    expected: Some(*expected),
+                    actual: None,
+                });
+            }
+            while let Some(turn) = turns.next_if(|t| t.tick == tick) {
+                state.turn_player(turn.player, turn.direction);
+            }
+            let mut slither_results = state.tick_all();
+            let expected_rivals = self.rival_outcomes.get(tick).map_or(&[][..], |r| &r[..]);
+            let expected = std::iter::once(expected).chain(expected_rivals);
+            for (player, (slither_result, expected)) in
+                slither_results.iter().zip(expected).enumerate()
+            {
+                let actual = ReplayOutcome::from(slither_result);
+                if &actual != expected {
+                    return Err(Divergence {
+                        tick,
+                        player,
+                        expected: Some(*expected),
+                        actual: Some(actual),
+                    });
+                }
+            }
+            results.push(slither_results.swap_remove(0));
+        }
+        Ok(results)
+    }
+
+    /// checks that this replay still plays out the way it was recorded
+    pub fn verify(&self) -> Result<(), Divergence> {
+        self.play().map(|_| ())
+    }
+
+    /// where the last game's replay is saved in the data directory
+    pub fn default_path() -> io::Result<PathBuf> {
+        Ok(data_dir()?.join("replay.json"))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self).map_err(io::Error::from)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let replay: Replay = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        if replay.version > REPLAY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "replay was written with format version {}, but this build only understands up to version {}",
+                    replay.version, REPLAY_VERSION
+                ),
+            ));
+        }
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded_game(seed: u64) -> State {
+        let mut state = State::with_seed(8, 8, seed);
+        state.start_recording();
+        state.turn_snek(Direction::Down);
+        state.tick();
+        state.tick();
+        state.turn_snek(Direction::Right);
+        // rejected turns are recorded too
+        state.turn_snek(Direction::Up);
+        while !matches!(state.tick(), SlitherResult::Died(_)) {}
+        state
+    }
+
+    #[test]
+    fn it_records_turns_keyed_by_tick() {
+        let state = recorded_game(7);
+        let replay = state.get_replay().unwrap();
+        assert_eq!(replay.get_seed(), 7);
+        assert_eq!(replay.get_dimensions(), (8, 8));
+        assert_eq!(
+            replay.get_turns(),
+            &[
+                ReplayTurn {
+                    tick: 0,
+                    player: 0,
+                    direction: Direction::Down
+                },
+                ReplayTurn {
+                    tick: 2,
+                    player: 0,
+                    direction: Direction::Right
+                },
+                ReplayTurn {
+                    tick: 2,
+                    player: 0,
+                    direction: Direction::Up
+                },
+            ]
+        );
+        assert_eq!(
+            replay.get_outcomes().last(),
+            Some(&ReplayOutcome::Died(DeathCause::Wall))
+        );
+    }
+
+    #[test]
+    fn it_plays_back_the_same_results() {
+        let state = recorded_game(11);
+        let replay = state.get_replay().unwrap();
+        let results = replay.play().unwrap();
+        assert_eq!(results.len(), replay.count_ticks());
+        assert_eq!(results.last(), Some(&SlitherResult::Died(DeathCause::Wall)));
+        assert_eq!(
+            results.first().unwrap().get_direction(),
+            Some(Direction::Down)
+        );
+    }
+
+    #[test]
+    fn it_detects_divergence() {
+        let state = recorded_game(3);
+        let mut replay = state.get_replay().unwrap().clone();
+        // pretend the snek died a different way on the last tick
+        let last_tick = replay.count_ticks() - 1;
+        *replay.outcomes.last_mut().unwrap() = ReplayOutcome::Died(DeathCause::Tail);
+        let divergence = replay.verify().unwrap_err();
+        assert_eq!(divergence.tick, last_tick);
+        assert_eq!(
+            divergence.expected,
+            Some(ReplayOutcome::Died(DeathCause::Tail))
+        );
+        assert_eq!(
+            divergence.actual,
+            Some(ReplayOutcome::Died(DeathCause::Wall))
+        );
+    }
+
+    #[test]
+    fn it_detects_games_that_end_early() {
+        let state = recorded_game(5);
+        let mut replay = state.get_replay().unwrap().clone();
+        replay.outcomes.push(ReplayOutcome::AteTheWorld);
+        let divergence = replay.verify().unwrap_err();
+        assert_eq!(divergence.tick, replay.count_ticks() - 1);
+        assert_eq!(divergence.actual, None);
+    }
+
+    #[test]
+    fn it_plays_back_with_the_same_rules() {
+        let mut state = State::with_options(6, 6, 21, GameOptions::wraparound());
+        state.start_recording();
+        for _ in 0..20 {
+            state.tick();
+        }
+        let replay = state.get_replay().unwrap();
+        assert_eq!(replay.get_options(), GameOptions::wraparound());
+        assert_eq!(replay.play().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn it_plays_back_both_players() {
+        let mut state = State::with_options(10, 10, 17, GameOptions::two_player());
+        state.start_recording();
+        state.turn_player(1, Direction::Up);
+        state.tick_all();
+        state.turn_player(0, Direction::Down);
+        while !state.is_game_over() {
+            state.tick_all();
+        }
+        let mut replay = state.get_replay().unwrap().clone();
+        assert_eq!(replay.get_turns()[0].player, 1);
+        assert_eq!(replay.get_rival_outcomes().len(), replay.count_ticks());
+        assert!(replay.verify().is_ok());
+        // pretend player two went somewhere else on the first tick
+        replay.rival_outcomes[0][0] = ReplayOutcome::Died(DeathCause::Wall);
+        let divergence = replay.verify().unwrap_err();
+        assert_eq!((divergence.tick, divergence.player), (0, 1));
+    }
+
+    #[test]
+    fn it_round_trips_through_a_file() {
+        let state = recorded_game(13);
+        let replay = state.get_replay().unwrap();
+        let path = std::env::temp_dir().join(format!("snek-replay-{}.json", std::process::id()));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&loaded, replay);
+        assert!(loaded.verify().is_ok());
+    }
+
+    #[test]
+    fn it_refuses_replays_from_the_future() {
+        let mut replay = Replay::new(&State::with_seed(8, 8, 1));
+        replay.version = REPLAY_VERSION + 1;
+        let path =
+            std::env::temp_dir().join(format!("snek-future-replay-{}.json", std::process::id()));
+        replay.save(&path).unwrap();
+        let error = Replay::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,19 +1,144 @@
-#[derive(Debug, Clone, Copy)]
+use crate::core::{Direction, Scoring};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Scoreboard {
     score: usize,
+    /// where the score came from
+    #[serde(default)]
+    breakdown: Breakdown,
+    /// how many apples in a row were eaten close enough together, after the first
+    #[serde(default)]
+    combo: usize,
+    /// how many ticks it's been since the last apple, if there's been one
+    #[serde(default)]
+    since_apple: Option<usize>,
+    /// how many ticks the snek has been alive for
+    #[serde(default)]
+    ticks_alive: usize,
+    /// how many ticks the snek has gone without turning or eating
+    #[serde(default)]
+    idle: usize,
+    /// which way the snek went last tick
+    #[serde(default)]
+    heading: Option<Direction>,
+}
+
+/// where a player's points came from, for showing once the game's over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Breakdown {
+    /// what the apples were worth on their own
+    pub apples: usize,
+    /// what eating apples back to back was worth on top
+    pub combos: usize,
+    /// what the apples gained or lost for the size of the board
+    pub board_size: isize,
+    /// points for staying alive
+    pub survival: usize,
+    /// points taken off for going a long time without turning or eating
+    pub idling: usize,
+}
+
+impl Breakdown {
+    /// one line for everything that counted towards the score. a score
+    /// that only ever came from apples doesn't need breaking down.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.combos == 0 && self.board_size == 0 && self.survival == 0 && self.idling == 0 {
+            return lines;
+        }
+        lines.push(format!("apples: {}", self.apples));
+        if self.combos > 0 {
+            lines.push(format!("combos: +{}", self.combos));
+        }
+        if self.board_size != 0 {
+            lines.push(format!("board sssize: {:+}", self.board_size));
+        }
+        if self.survival > 0 {
+            lines.push(format!("sssurvival: +{}", self.survival));
+        }
+        if self.idling > 0 {
+            lines.push(format!("idling: -{}", self.idling));
+        }
+        lines
+    }
 }
 
 impl Scoreboard {
     pub fn new() -> Self {
-        Self { score: 0 }
+        Self {
+            score: 0,
+            breakdown: Breakdown::default(),
+            combo: 0,
+            since_apple: None,
+            ticks_alive: 0,
+            idle: 0,
+            heading: None,
+        }
     }
 
     pub fn get_score(&self) -> usize {
         self.score
     }
 
+    pub fn get_breakdown(&self) -> Breakdown {
+        self.breakdown
+    }
+
+    /// how many apples in a row have been eaten back to back, after the first
+    pub fn get_combo(&self) -> usize {
+        self.combo
+    }
+
     pub fn increment_score(&mut self) {
-        self.score += 1;
+        self.add_points(1);
+    }
+
+    /// adds points just as they are, without any of the scoring rules
+    pub fn add_points(&mut self, points: usize) {
+        self.score += points;
+        self.breakdown.apples += points;
+    }
+
+    /// scores an apple worth `points` on a board with `cells` free cells
+    pub fn eat(&mut self, points: usize, scoring: &Scoring, cells: usize) {
+        if points == 0 {
+            return;
+        }
+        self.combo = match self.since_apple {
+            Some(since_apple) if since_apple <= scoring.combo_ticks => self.combo + 1,
+            _ => 0,
+        };
+        let (combo_points, scaled_points) = scoring.get_apple_points(points, self.combo, cells);
+        self.score += scaled_points;
+        self.breakdown.apples += points;
+        self.breakdown.combos += combo_points - points;
+        self.breakdown.board_size += scaled_points as isize - combo_points as isize;
+        self.since_apple = Some(0);
+        self.idle = 0;
+    }
+
+    /// counts a tick the snek lived through, having gone `heading`
+    pub fn tick(&mut self, heading: Direction, scoring: &Scoring) {
+        self.ticks_alive += 1;
+        self.since_apple = self.since_apple.map(|since_apple| since_apple + 1);
+        if self.heading.is_some_and(|h| h != heading) {
+            self.idle = 0;
+        } else {
+            self.idle += 1;
+        }
+        self.heading = Some(heading);
+        if scoring.survival_ticks > 0 && self.ticks_alive.is_multiple_of(scoring.survival_ticks) {
+            self.score += scoring.survival_points;
+            self.breakdown.survival += scoring.survival_points;
+        }
+        if scoring.idle_ticks > 0 && self.idle > 0 && self.idle.is_multiple_of(scoring.idle_ticks) {
+            // idling can take away every point, but no more than that
+            let penalty = scoring.idle_penalty.min(self.score);
+            self.score -= penalty;
+            self.breakdown.idling += penalty;
+        }
     }
 }
 
@@ -42,4 +167,101 @@ mod tests {
         scoreboard.increment_score();
         assert_eq!(scoreboard.get_score(), 2);
     }
+
+    #[test]
+    fn it_adds_bonus_points() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.increment_score();
+        scoreboard.add_points(5);
+        assert_eq!(scoreboard.get_score(), 6);
+    }
+
+    fn combos() -> Scoring {
+        Scoring {
+            combo_ticks: 3,
+            combo_bonus: 100,
+            max_combo: 5,
+            ..Scoring::classic()
+        }
+    }
+
+    #[test]
+    fn apples_back_to_back_make_a_combo() {
+        let scoring = combos();
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.eat(1, &scoring, 100);
+        scoreboard.tick(Direction::Right, &scoring);
+        scoreboard.eat(1, &scoring, 100);
+        assert_eq!(scoreboard.get_combo(), 1);
+        assert_eq!(scoreboard.get_score(), 1 + 2);
+        for _ in 0..4 {
+            scoreboard.tick(Direction::Right, &scoring);
+        }
+        // too long since the last one
+        scoreboard.eat(1, &scoring, 100);
+        assert_eq!(scoreboard.get_combo(), 0);
+        assert_eq!(scoreboard.get_score(), 1 + 2 + 1);
+        let breakdown = scoreboard.get_breakdown();
+        assert_eq!(breakdown.apples, 3);
+        assert_eq!(breakdown.combos, 1);
+    }
+
+    #[test]
+    fn staying_alive_is_worth_points() {
+        let scoring = Scoring {
+            survival_points: 2,
+            survival_ticks: 10,
+            ..Scoring::classic()
+        };
+        let mut scoreboard = Scoreboard::new();
+        for _ in 0..25 {
+            scoreboard.tick(Direction::Up, &scoring);
+        }
+        assert_eq!(scoreboard.get_score(), 4);
+        assert_eq!(scoreboard.get_breakdown().survival, 4);
+    }
+
+    #[test]
+    fn idling_costs_points_until_the_snek_turns() {
+        let scoring = Scoring {
+            idle_ticks: 5,
+            idle_penalty: 1,
+            ..Scoring::classic()
+        };
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.add_points(3);
+        for _ in 0..10 {
+            scoreboard.tick(Direction::Left, &scoring);
+        }
+        assert_eq!(scoreboard.get_score(), 1);
+        scoreboard.tick(Direction::Up, &scoring);
+        for _ in 0..4 {
+            scoreboard.tick(Direction::Up, &scoring);
+        }
+        assert_eq!(scoreboard.get_score(), 1);
+        for _ in 0..20 {
+            scoreboard.tick(Direction::Up, &scoring);
+        }
+        // it never goes below zero
+        assert_eq!(scoreboard.get_score(), 0);
+        assert_eq!(scoreboard.get_breakdown().idling, 3);
+    }
+
+    #[test]
+    fn classic_scores_dont_need_breaking_down() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.eat(3, &Scoring::classic(), 100);
+        assert!(scoreboard.get_breakdown().describe().is_empty());
+        let breakdown = Breakdown {
+            apples: 10,
+            combos: 4,
+            board_size: -2,
+            survival: 0,
+            idling: 1,
+        };
+        assert_eq!(
+            breakdown.describe(),
+            vec!["apples: 10", "combos: +4", "board sssize: -2", "idling: -1"]
+        );
+    }
 }
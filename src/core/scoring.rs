@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// how many points a snek gets, and for what. the default is the classic
+/// rules, where every apple is worth what it says and nothing else counts.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scoring {
+    /// apples eaten within this many ticks of the one before keep a combo
+    /// going. zero means there are no combos.
+    pub combo_ticks: usize,
+    /// how much more each apple in a combo is worth than the one before,
+    /// as a percentage of what it's worth on its own
+    pub combo_bonus: usize,
+    /// the most apples a combo counts before it stops getting any better
+    pub max_combo: usize,
+    /// points for every `survival_ticks` ticks a snek stays alive
+    pub survival_points: usize,
+    /// zero means there's no bonus for surviving
+    pub survival_ticks: usize,
+    /// how many cells the board's meant to have for apples to be worth
+    /// what they say. they're worth more on a bigger board and less on a
+    /// smaller one, in step with how far across it is, so scores from
+    /// different sized boards line up. `None` leaves them be.
+    pub board_size: Option<usize>,
+    /// how long a snek can go without turning or eating before it's
+    /// idling, and how often it's docked points for it after that.
+    /// zero means nothing counts as idling.
+    pub idle_ticks: usize,
+    /// points lost every `idle_ticks` ticks spent idling
+    pub idle_penalty: usize,
+}
+
+impl Scoring {
+    /// every apple is worth what it says, and nothing else counts
+    pub fn classic() -> Self {
+        Self::default()
+    }
+
+    /// combos for eating apples back to back, a trickle of points for
+    /// staying alive, and points off for going round and round in a straight line
+    pub fn arcade() -> Self {
+        Self {
+            combo_ticks: 15,
+            combo_bonus: 50,
+            max_combo: 4,
+            survival_points: 1,
+            survival_ticks: 50,
+            board_size: Some(400),
+            idle_ticks: 100,
+            idle_penalty: 1,
+        }
+    }
+
+    /// what `points` are worth as the `combo`th apple in a row, where
+    /// zero is an apple on its own, on a board with `cells` free cells.
+    /// returns the points without the board size taken into account, then with.
+    pub fn get_apple_points(&self, points: usize, combo: usize, cells: usize) -> (usize, usize) {
+        if points == 0 {
+            return (0, 0);
+        }
+        let combo = combo.min(self.max_combo);
+        let multiplier = 1.0 + (combo * self.combo_bonus) as f64 / 100.0;
+        let scale = match self.board_size {
+            Some(board_size) => (cells as f64 / board_size.max(1) as f64).sqrt(),
+            None => 1.0,
+        };
+        let worth = |scale: f64| ((points as f64 * multiplier * scale).round() as usize).max(1);
+        (worth(1.0), worth(scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_apples_are_worth_what_they_say() {
+        let scoring = Scoring::classic();
+        assert_eq!(scoring.get_apple_points(1, 3, 20), (1, 1));
+        assert_eq!(scoring.get_apple_points(5, 0, 10_000), (5, 5));
+    }
+
+    #[test]
+    fn combos_are_worth_more_up_to_a_point() {
+        let scoring = Scoring {
+            combo_bonus: 50,
+            max_combo: 2,
+            ..Scoring::classic()
+        };
+        assert_eq!(scoring.get_apple_points(2, 0, 100), (2, 2));
+        assert_eq!(scoring.get_apple_points(2, 1, 100), (3, 3));
+        assert_eq!(scoring.get_apple_points(2, 2, 100), (4, 4));
+        assert_eq!(scoring.get_apple_points(2, 9, 100), (4, 4));
+    }
+
+    #[test]
+    fn apples_are_worth_more_on_bigger_boards() {
+        let scoring = Scoring {
+            board_size: Some(100),
+            ..Scoring::classic()
+        };
+        assert_eq!(scoring.get_apple_points(2, 0, 100), (2, 2));
+        assert_eq!(scoring.get_apple_points(2, 0, 400), (2, 4));
+        assert_eq!(scoring.get_apple_points(2, 0, 25), (2, 1));
+        // an apple is always worth something
+        assert_eq!(scoring.get_apple_points(1, 0, 1), (1, 1));
+        assert_eq!(scoring.get_apple_points(0, 0, 400), (0, 0));
+    }
+}
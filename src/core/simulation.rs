@@ -0,0 +1,274 @@
+use crate::core::{Bot, DeathCause, GameOptions, NoCycle, SlitherResult, State};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    time::Instant,
+};
+
+/// how a bunch of games played by a bot are set up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationOptions {
+    pub bot: Bot,
+    pub width: usize,
+    pub height: usize,
+    pub game: GameOptions,
+    pub games: usize,
+    /// the first game's seed. each game after it gets the next one.
+    pub seed: u64,
+    /// how many ticks a bot can go without scoring before it's given
+    /// up on, since some bots go round in circles forever. it's twice
+    /// as many as there are cells on the board if it isn't set.
+    pub patience: Option<usize>,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        Self {
+            bot: Bot::default(),
+            width: 20,
+            height: 20,
+            game: GameOptions::default(),
+            games: 1000,
+            seed: 0,
+            patience: None,
+        }
+    }
+}
+
+/// how one game played by a bot ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ending {
+    Died(DeathCause),
+    AteTheWorld,
+    GaveUp,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GameResult {
+    score: usize,
+    ticks: usize,
+    ending: Ending,
+}
+
+/// how well a bot did over a lot of games
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationStats {
+    pub bot: String,
+    pub width: usize,
+    pub height: usize,
+    pub games: usize,
+    pub mean_score: f64,
+    pub median_score: f64,
+    pub max_score: usize,
+    pub mean_ticks: f64,
+    pub median_ticks: f64,
+    pub max_ticks: usize,
+    /// how many games the snek ate the world in
+    pub wins: usize,
+    pub win_rate: f64,
+    /// how many sneks died of each cause
+    pub deaths: BTreeMap<DeathCause, usize>,
+    /// how many games the bot stopped scoring in and was given up on
+    pub gave_up: usize,
+    pub seconds: f64,
+    pub games_per_second: f64,
+}
+
+/// plays lots of games with a bot all at once, without drawing any of them
+pub fn simulate(options: SimulationOptions) -> Result<SimulationStats, NoCycle> {
+    let started = Instant::now();
+    let results = (0..options.games as u64)
+        .into_par_iter()
+        .map(|game| play(&options, options.seed.wrapping_add(game)))
+        .collect::<Result<Vec<GameResult>, NoCycle>>()?;
+    let seconds = started.elapsed().as_secs_f64();
+    let scores: Vec<usize> = results.iter().map(|r| r.score).collect();
+    let ticks: Vec<usize> = results.iter().map(|r| r.ticks).collect();
+    let count = |ending: Ending| results.iter().filter(|r| r.ending == ending).count();
+    let mut deaths = BTreeMap::new();
+    for result in &results {
+        if let Ending::Died(cause) = result.ending {
+            *deaths.entry(cause).or_insert(0) += 1;
+        }
+    }
+    let wins = count(Ending::AteTheWorld);
+    Ok(SimulationStats {
+        bot: options.bot.describe().to_string(),
+        width: options.width,
+        height: options.height,
+        games: results.len(),
+        mean_score: mean(&scores),
+        median_score: median(&scores),
+        max_score: scores.iter().copied().max().unwrap_or(0),
+        mean_ticks: mean(&ticks),
+        median_ticks: median(&ticks),
+        max_ticks: ticks.iter().copied().max().unwrap_or(0),
+        wins,
+        win_rate: ratio(wins, results.len()),
+        deaths,
+        gave_up: count(Ending::GaveUp),
+        seconds,
+        games_per_second: results.len() as f64 / seconds.max(f64::EPSILON),
+    })
+}
+
+fn play(options: &SimulationOptions, seed: u64) -> Result<GameResult, NoCycle> {
+    let mut state = State::with_options(options.width, options.height, seed, options.game);
+    let mut controllers = vec![options.bot.to_controller(state.get_walls())?];
+    let patience = options
+        .patience
+        .unwrap_or(options.width * options.height * 2);
+    let (mut score, mut scored_at) = (0, 0);
+    let ending = loop {
+        match state.tick_with(&mut controllers).swap_remove(0) {
+            SlitherResult::Died(cause) => break Ending::Died(cause),
+            SlitherResult::AteTheWorld => break Ending::AteTheWorld,
+            _ if state.get_score() != score => {
+                score = state.get_score();
+                scored_at = state.get_tick();
+            }
+            _ if state.get_tick() - scored_at > patience => break Ending::GaveUp,
+            _ => {}
+        }
+    };
+    Ok(GameResult {
+        score: state.get_score(),
+        ticks: state.get_tick(),
+        ending,
+    })
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+fn mean(values: &[usize]) -> f64 {
+    ratio(values.iter().sum(), values.len())
+}
+
+fn median(values: &[usize]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) as f64 / 2.0,
+        len => values[len / 2] as f64,
+    }
+}
+
+impl Display for SimulationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} played {} games on a {}x{} board in {:.2}s ({:.1} games a second)",
+            self.bot, self.games, self.width, self.height, self.seconds, self.games_per_second
+        )?;
+        writeln!(
+            f,
+            "ssscore   mean {:.2}   median {}   max {}",
+            self.mean_score, self.median_score, self.max_score
+        )?;
+        writeln!(
+            f,
+            "ticksss   mean {:.2}   median {}   max {}",
+            self.mean_ticks, self.median_ticks, self.max_ticks
+        )?;
+        writeln!(
+            f,
+            "ate the world {} times ({:.1}%)",
+            self.wins,
+            self.win_rate * 100.0
+        )?;
+        for (cause, count) in &self.deaths {
+            writeln!(f, "{} {} times", cause.describe(), count)?;
+        }
+        if self.gave_up > 0 {
+            writeln!(f, "went round in circles {} times", self.gave_up)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_game_ends_somehow() {
+        let options = SimulationOptions {
+            bot: Bot::Greedy,
+            width: 10,
+            height: 10,
+            games: 40,
+            ..SimulationOptions::default()
+        };
+        let stats = simulate(options).unwrap();
+        assert_eq!(stats.games, 40);
+        let deaths: usize = stats.deaths.values().sum();
+        assert_eq!(deaths + stats.wins + stats.gave_up, 40);
+        assert!(stats.max_score as f64 >= stats.mean_score);
+    }
+
+    #[test]
+    fn the_same_seeds_play_the_same_games() {
+        let options = SimulationOptions {
+            width: 10,
+            height: 8,
+            games: 10,
+            seed: 3,
+            ..SimulationOptions::default()
+        };
+        let stats = simulate(options).unwrap();
+        let again = simulate(options).unwrap();
+        assert_eq!(stats.mean_score, again.mean_score);
+        assert_eq!(stats.deaths, again.deaths);
+    }
+
+    #[test]
+    fn the_hamiltonian_bot_always_wins() {
+        let options = SimulationOptions {
+            bot: Bot::Hamiltonian,
+            width: 8,
+            height: 7,
+            games: 20,
+            ..SimulationOptions::default()
+        };
+        let stats = simulate(options).unwrap();
+        assert_eq!(stats.win_rate, 1.0);
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"win_rate\":1.0"));
+    }
+
+    #[test]
+    fn deaths_are_reported_under_names_of_their_own() {
+        let deaths = BTreeMap::from([(DeathCause::OtherSnek, 2), (DeathCause::Wall, 1)]);
+        let json = serde_json::to_string(&deaths).unwrap();
+        assert_eq!(json, r#"{"wall":1,"other_snek":2}"#);
+    }
+
+    #[test]
+    fn it_says_when_the_bot_cant_play() {
+        let options = SimulationOptions {
+            bot: Bot::Hamiltonian,
+            width: 7,
+            height: 7,
+            ..SimulationOptions::default()
+        };
+        assert!(simulate(options).is_err());
+    }
+
+    #[test]
+    fn medians_take_the_middle() {
+        assert_eq!(median(&[5, 1, 3]), 3.0);
+        assert_eq!(median(&[4, 1, 3, 2]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+    }
+}
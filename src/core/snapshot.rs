@@ -0,0 +1,95 @@
+use crate::core::{Apple, GameOptions, Player, PowerUp};
+
+use rand::Rng;
+use rand_pcg::Pcg64;
+
+use std::hash::{Hash, Hasher};
+
+/// everything about a game that changes from one tick to the next.
+/// the walls never change, so taking a snapshot every tick and
+/// restoring an old one costs about as much as the sneks are long.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub(crate) players: Vec<Player>,
+    pub(crate) apples: Vec<Apple>,
+    pub(crate) power_ups: Vec<PowerUp>,
+    pub(crate) rng: Pcg64,
+    pub(crate) tick_count: usize,
+    pub(crate) options: GameOptions,
+}
+
+impl Snapshot {
+    pub fn get_tick(&self) -> usize {
+        self.tick_count
+    }
+
+    /// see [`State::get_hash`](crate::core::State::get_hash)
+    pub fn get_hash(&self) -> u64 {
+        hash_game(
+            &self.players,
+            &self.apples,
+            &self.power_ups,
+            &self.rng,
+            self.tick_count,
+        )
+    }
+}
+
+/// hashes everything that can change while a game is played,
+/// so two games that have played out the same way hash the same
+pub(crate) fn hash_game(
+    players: &[Player],
+    apples: &[Apple],
+    power_ups: &[PowerUp],
+    rng: &Pcg64,
+    tick_count: usize,
+) -> u64 {
+    let mut hasher = FnvHasher::default();
+    tick_count.hash(&mut hasher);
+    players.hash(&mut hasher);
+    apples.hash(&mut hasher);
+    power_ups.hash(&mut hasher);
+    // the rng doesn't hash, but where it's up to shows in what it says next
+    rng.clone().gen::<u64>().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 64 bit FNV-1a, which unlike the standard library's
+/// hasher gives the same answer in every process
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    // the same on every platform, whatever size a usize is
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv_matches_the_reference() {
+        let mut hasher = FnvHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+}
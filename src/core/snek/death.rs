@@ -1,8 +1,28 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+/// saved under names of their own, so anything reading the json doesn't
+/// depend on what the variants are called. games and stats saved before
+/// that used the variant names, which still load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DeathCause {
+    #[serde(alias = "Wall")]
     Wall,
+    #[serde(alias = "Tail")]
     Tail,
+    #[serde(alias = "Resized")]
     Resized,
+    #[serde(alias = "Poison")]
+    Poison,
+    /// it ran into another snek's body
+    #[serde(alias = "OtherSnek")]
+    OtherSnek,
+    /// it met another snek head-on and lost
+    #[serde(alias = "HeadOn")]
+    HeadOn,
+    /// whoever was steering it went away
+    #[serde(alias = "Disconnected")]
+    Disconnected,
 }
 
 impl DeathCause {
@@ -11,6 +31,10 @@ impl DeathCause {
             DeathCause::Wall => "it ran into the wall",
             DeathCause::Tail => "it ran into its own tail",
             DeathCause::Resized => "you resized the terminal",
+            DeathCause::Poison => "it ate a poison apple",
+            DeathCause::OtherSnek => "it ran into another snek",
+            DeathCause::HeadOn => "it met another snek head-on",
+            DeathCause::Disconnected => "its player disconnected",
         }
     }
 }
@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -7,6 +9,13 @@ pub enum Direction {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
     pub fn get_tail_char(&self) -> char {
         match self {
             Direction::Up | Direction::Down => '|',
@@ -27,6 +36,21 @@ impl Direction {
         self.opposite().eq(attempted_direction)
     }
 
+    /// which way is left for a snek heading this way
+    pub fn left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// which way is right for a snek heading this way
+    pub fn right(&self) -> Direction {
+        self.left().opposite()
+    }
+
     pub fn opposite(&self) -> Direction {
         match self {
             Direction::Up => Direction::Down,
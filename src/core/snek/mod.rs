@@ -11,8 +11,9 @@ pub use slither::{SlitherAction, SlitherResult};
 use crate::core::Position;
 
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Snek {
     segments: Vec<Segment>,
     alive: bool,
@@ -64,6 +65,16 @@ impl Snek {
         self.segments.clone()
     }
 
+    /// every segment from the end of the tail to the head, without copying them
+    pub fn iter_segments(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter()
+    }
+
+    /// the very end of the tail, which is the head for a baby snek
+    pub fn get_tail_end(&self) -> &Segment {
+        &self.segments[0]
+    }
+
     pub fn get_segment_positions(&self) -> Vec<Position> {
         self.segments.par_iter().map(|s| s.get_position()).collect()
     }
@@ -93,14 +104,21 @@ impl Snek {
     }
 
     pub fn grow(&mut self, direction: &Direction) {
+        // my thinking is that it's safe to unwrap here because we wouldn't
+        // be growing if there wasn't an apple, and an apple couldn't have
+        // an invalid position. right?
+        let new_position = self.get_head().get_position().neighbor(*direction).unwrap();
+        self.grow_to(direction, new_position);
+    }
+
+    /// grows the snek's head into `new_position`,
+    /// which lets the board decide what's next to what
+    pub fn grow_to(&mut self, direction: &Direction, new_position: Position) {
         let mut new_head = self.get_head().to_owned();
         let old_head = self.get_head_mut();
         old_head.make_tail();
         old_head.set_direction(direction);
-        // my thinking is that it's safe to unwrap here because we wouldn't
-        // be growing if there wasn't an apple, and an apple couldn't have
-        // an invalid position. right?
-        new_head.set_position(&new_head.get_position().neighbor(*direction).unwrap());
+        new_head.set_position(&new_position);
         new_head.set_direction(direction);
         self.segments.push(new_head);
     }
@@ -113,19 +131,19 @@ impl Snek {
         self.segments.remove(0);
     }
 
-    pub(crate) fn will_i_run_into_myssself(
-        &self,
-        potential_head: &Position,
-        will_grow: bool,
-    ) -> bool {
-        let mut will_i_run_into_myself = false;
-        let skip_tail = if will_grow { 1 } else { 0 };
-        self.get_tail().iter().skip(skip_tail).for_each(|s| {
-            if &s.get_position() == potential_head {
-                will_i_run_into_myself = true;
-            }
-        });
-        will_i_run_into_myself
+    pub fn slither_to(&mut self, direction: &Direction, new_position: Position) {
+        self.grow_to(direction, new_position);
+        self.segments.remove(0);
+    }
+
+    /// takes up to `count` segments off the end of the snek's tail,
+    /// never its head, returning where they used to be
+    pub fn shrink(&mut self, count: usize) -> Vec<Position> {
+        let count = count.min(self.segments.len() - 1);
+        self.segments
+            .drain(0..count)
+            .map(|s| s.get_position())
+            .collect()
     }
 
     pub(crate) fn kill(&mut self) {
@@ -1,6 +1,8 @@
 use crate::core::{Direction, Position};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Segment {
     position: Position,
     direction: Direction,
@@ -104,7 +106,7 @@ impl Segment {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SegmentType {
     DeadHead,
     LeftSegment,
@@ -1,4 +1,4 @@
-use crate::core::{DeathCause, Direction, Position, Segment};
+use crate::core::{AppleKind, DeathCause, Direction, Position, Segment};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SlitherAction {
@@ -7,7 +7,10 @@ pub enum SlitherAction {
         direction: Direction,
     },
     Grow(Direction),
+    Shrink(Direction),
     Slither(Direction),
+    /// the snek's shield stopped it running into a wall
+    Shield(Direction),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,12 +20,24 @@ pub enum SlitherResult {
         direction: Direction,
         segments: Vec<Segment>,
         slime_trail: Position,
+        eaten: AppleKind,
+    },
+    /// the snek ate a poison apple and lost the end of its tail
+    Shrank {
+        direction: Direction,
+        segments: Vec<Segment>,
+        slime_trail: Vec<Position>,
     },
     Slithered {
         direction: Direction,
         segments: Vec<Segment>,
         slime_trail: Position,
     },
+    /// the snek's shield stopped it running into a wall,
+    /// so it stayed where it was
+    Shielded {
+        direction: Direction,
+    },
     AteTheWorld,
 }
 
@@ -36,8 +51,19 @@ impl SlitherResult {
                 direction,
                 slime_trail,
                 segments: _,
+                eaten,
             } => format!(
-                "snek grew {} and left a slime trail at {:?}",
+                "snek ate a {} going {} and left a slime trail at {:?}",
+                eaten.describe(),
+                direction.describe(),
+                slime_trail
+            ),
+            SlitherResult::Shrank {
+                direction,
+                slime_trail,
+                segments: _,
+            } => format!(
+                "snek ate a poison apple going {} and shrank out of {:?}",
                 direction.describe(),
                 slime_trail
             ),
@@ -52,6 +78,10 @@ impl SlitherResult {
                     slime_trail
                 )
             }
+            SlitherResult::Shielded { direction } => format!(
+                "snek's shield stopped it running into the wall going {}",
+                direction.describe()
+            ),
             SlitherResult::AteTheWorld => "snek ate the world".to_string(),
         }
     }
@@ -62,6 +92,12 @@ impl SlitherResult {
                 direction,
                 segments: _,
                 slime_trail: _,
+                eaten: _,
+            }
+            | SlitherResult::Shrank {
+                direction,
+                segments: _,
+                slime_trail: _,
             }
             | SlitherResult::Slithered {
                 direction,
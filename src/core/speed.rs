@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+/// how fast a game ticks, and how much faster it gets as the sneks score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Speed {
+    /// how many times a second the game ticks at the start
+    pub ticks_per_second: f64,
+    /// how many more ticks a second it gets every `points` points
+    pub acceleration: f64,
+    /// how many points it takes to speed up. a plain apple is worth one.
+    pub points: usize,
+    /// the fastest the game ever ticks, before any power-ups
+    pub max_ticks_per_second: f64,
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Self::constant(6.0)
+    }
+}
+
+impl Speed {
+    /// a game that never speeds up
+    pub fn constant(ticks_per_second: f64) -> Self {
+        Self {
+            ticks_per_second,
+            acceleration: 0.0,
+            points: 1,
+            max_ticks_per_second: ticks_per_second,
+        }
+    }
+
+    /// a game that starts off slow and gets faster with every apple
+    pub fn progressive() -> Self {
+        Self {
+            ticks_per_second: 5.0,
+            acceleration: 0.5,
+            points: 2,
+            max_ticks_per_second: 20.0,
+        }
+    }
+
+    /// how many times a second the game ticks once the best snek has scored `score`
+    pub fn get_ticks_per_second(&self, score: usize) -> f64 {
+        let speed_ups = (score / self.points.max(1)) as f64;
+        (self.ticks_per_second + self.acceleration * speed_ups)
+            .min(self.max_ticks_per_second)
+            .max(f64::EPSILON)
+    }
+
+    /// how long a tick lasts once the best snek has scored `score`
+    pub fn get_tick_duration(&self, score: usize) -> Duration {
+        Duration::from_secs_f64(1.0 / self.get_ticks_per_second(score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_games_never_speed_up() {
+        let speed = Speed::constant(8.0);
+        assert_eq!(speed.get_ticks_per_second(0), 8.0);
+        assert_eq!(speed.get_ticks_per_second(500), 8.0);
+        assert_eq!(speed.get_tick_duration(3), Duration::from_millis(125));
+    }
+
+    #[test]
+    fn progressive_games_speed_up_to_a_point() {
+        let speed = Speed {
+            ticks_per_second: 4.0,
+            acceleration: 1.0,
+            points: 3,
+            max_ticks_per_second: 6.0,
+        };
+        assert_eq!(speed.get_ticks_per_second(2), 4.0);
+        assert_eq!(speed.get_ticks_per_second(3), 5.0);
+        assert_eq!(speed.get_ticks_per_second(7), 6.0);
+        assert_eq!(speed.get_ticks_per_second(100), 6.0);
+    }
+}
@@ -1,177 +1,1064 @@
 use crate::core::{
-    Apple, DeathCause, Direction, Position, Scoreboard, SlitherAction, SlitherResult, Snek, Walls,
+    event::Subscribers, occupancy::Occupancy, ActivePowerUp, Apple, AppleKind, DeathCause,
+    Direction, GameEvent, GameOptions, HeadOnRule, Player, Position, PowerUp, PowerUpKind, Replay,
+    Scoreboard, Segment, SlitherAction, SlitherResult, Snapshot, Snek, Walls,
+    POISON_APPLE_SEGMENTS, POWER_UP_CHANCE, POWER_UP_TICKS_ON_BOARD, TIMED_APPLE_TICKS,
 };
 
-use rand::{seq::SliceRandom, thread_rng};
+use crossbeam_channel::{Receiver, Sender};
+use rand::{seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand_pcg::Pcg64;
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug)]
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+    time::Duration,
+};
+
+/// the save file format version written by this build
+pub const SAVE_VERSION: u32 = 2;
+/// the fewest cells a board can have
+pub const MIN_BOARD_CELLS: usize = 16;
+/// the fewest cells a board can have across or down, so there's
+/// room inside the border for player one to start at (1, 1)
+pub const MIN_BOARD_SIDE: usize = 3;
+
+#[derive(Serialize, Deserialize)]
+struct SavedGame<S> {
+    version: u32,
+    state: S,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StateFile")]
 pub struct State {
     walls: Walls,
-    scoreboard: Scoreboard,
-    snek: Snek,
-    apple: Apple,
-    direction: Direction,
+    /// everyone playing, player one first
+    players: Vec<Player>,
+    apples: Vec<Apple>,
+    /// power-ups on the board waiting to be picked up
+    power_ups: Vec<PowerUp>,
+    /// what's in every cell, worked out again whenever a game is loaded
+    #[serde(skip)]
+    occupancy: Occupancy,
+    seed: u64,
+    rng: Pcg64,
+    tick_count: usize,
+    replay: Option<Replay>,
+    options: GameOptions,
+    /// whoever's listening to what happens in the game
+    #[serde(skip)]
+    subscribers: Subscribers,
+}
+
+/// games saved before there could be more than one snek kept
+/// the only snek, its direction, score and power-ups right in
+/// the state, and games saved before there could be more than
+/// one apple only have one
+#[derive(Deserialize)]
+struct StateFile {
+    walls: Walls,
+    players: Option<Vec<Player>>,
+    scoreboard: Option<Scoreboard>,
+    snek: Option<Snek>,
+    direction: Option<Direction>,
+    #[serde(default)]
+    active_power_ups: Vec<ActivePowerUp>,
+    #[serde(alias = "apple", deserialize_with = "deserialize_apples")]
+    apples: Vec<Apple>,
+    #[serde(default)]
+    power_ups: Vec<PowerUp>,
+    seed: u64,
+    rng: Pcg64,
+    tick_count: usize,
+    replay: Option<Replay>,
+    #[serde(default)]
+    options: GameOptions,
+}
+
+impl From<StateFile> for State {
+    fn from(state_file: StateFile) -> Self {
+        let players = match (state_file.players, state_file.snek) {
+            (Some(players), _) => players,
+            (None, snek) => vec![Player::with_parts(
+                snek.unwrap_or_default(),
+                state_file.direction,
+                state_file.scoreboard.unwrap_or_default(),
+                state_file.active_power_ups,
+            )],
+        };
+        let occupancy = Occupancy::new(
+            &state_file.walls,
+            &players,
+            &state_file.apples,
+            &state_file.power_ups,
+        );
+        Self {
+            walls: state_file.walls,
+            players,
+            apples: state_file.apples,
+            power_ups: state_file.power_ups,
+            occupancy,
+            seed: state_file.seed,
+            rng: state_file.rng,
+            tick_count: state_file.tick_count,
+            replay: state_file.replay,
+            options: state_file.options,
+            subscribers: Subscribers::default(),
+        }
+    }
+}
+
+fn deserialize_apples<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Apple>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Apples {
+        One(Apple),
+        Many(Vec<Apple>),
+    }
+    Ok(match Apples::deserialize(deserializer)? {
+        Apples::One(apple) => vec![apple],
+        Apples::Many(apples) => apples,
+    })
 }
 
 impl State {
     #[tracing::instrument(level = "info")]
     pub fn new(width: usize, height: usize) -> State {
-        if width * height < 16 {
-            panic!("width * height must be at least 16");
+        State::with_seed(width, height, thread_rng().gen())
+    }
+
+    /// whether a game can be played on a `width` by `height` board,
+    /// and if not, why not
+    pub fn check_board_size(width: usize, height: usize) -> Result<(), String> {
+        if width < MIN_BOARD_SIDE
+            || height < MIN_BOARD_SIDE
+            || width.saturating_mul(height) < MIN_BOARD_CELLS
+        {
+            return Err(format!(
+                "a {}x{} board is too sssmall, it needs to be at least {} cells across and down and {} cells in all",
+                width, height, MIN_BOARD_SIDE, MIN_BOARD_CELLS
+            ));
+        }
+        Ok(())
+    }
+
+    /// creates a game whose randomness is entirely
+    /// determined by `seed`. two games with the same
+    /// dimensions and seed play out identically
+    /// as long as the snek is turned the same way.
+    #[tracing::instrument(level = "info")]
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> State {
+        State::with_options(width, height, seed, GameOptions::default())
+    }
+
+    /// creates a seeded game played by a particular set of rules.
+    /// player one starts in the top left corner heading right,
+    /// and player two in the bottom right corner heading left.
+    #[tracing::instrument(level = "info")]
+    pub fn with_options(width: usize, height: usize, seed: u64, options: GameOptions) -> State {
+        if let Err(e) = State::check_board_size(width, height) {
+            panic!("{}", e);
+        }
+        if !(1..=2).contains(&options.players) {
+            panic!("there must be one or two players");
         }
-        let walls = Walls::new(width, height);
-        let scoreboard = Scoreboard::new();
-        let direction = Direction::Right;
+        let walls = Walls::with_topology(width, height, options.topology);
         // let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 2);
-        let snek = Snek::default();
-        // dummy apple
-        let apple = Apple::new(Position::new(2, 1));
+        let mut sneks = vec![Snek::default()];
+        if options.players == 2 {
+            sneks.push(Snek::baby_snek(
+                Position::new(width - 2, height - 2),
+                Direction::Left,
+            ));
+        }
+        State::from_parts(walls, sneks, Vec::new(), seed, options)
+    }
+
+    /// creates a seeded game on a custom board, with one player for
+    /// each snek, each heading whichever way its head points. if
+    /// there are fewer apples than the options ask for, the rest
+    /// are planted somewhere random.
+    pub fn from_parts(
+        walls: Walls,
+        sneks: Vec<Snek>,
+        apples: Vec<Apple>,
+        seed: u64,
+        mut options: GameOptions,
+    ) -> State {
+        if sneks.is_empty() {
+            panic!("there must be at least one snek");
+        }
+        options.topology = walls.get_topology();
+        options.players = sneks.len();
+        let players: Vec<Player> = sneks.into_iter().map(Player::new).collect();
+        let occupancy = Occupancy::new(&walls, &players, &apples, &[]);
         let mut state = State {
-            apple,
             walls,
-            snek,
-            scoreboard,
-            direction,
+            players,
+            apples,
+            power_ups: Vec::new(),
+            occupancy,
+            seed,
+            rng: Pcg64::seed_from_u64(seed),
+            tick_count: 0,
+            replay: None,
+            options,
+            subscribers: Subscribers::default(),
         };
-        state.new_apple();
+        state.grow_apples();
         state
     }
 
-    pub fn new_apple(&mut self) {
-        let mut possible_positions = Vec::new();
-        for x in self.walls.left_wall()..=self.walls.right_wall() {
-            for y in self.walls.top_wall()..=self.walls.bottom_wall() {
-                let position = Position::new(x, y);
-                let mut position_is_valid = true;
-                for segment in self.snek.get_segments() {
-                    if segment.get_position() == position {
-                        position_is_valid = false;
-                    }
-                }
-                for wall_position in self.get_walls().get_positions() {
-                    if wall_position == position {
-                        position_is_valid = false;
-                    }
-                }
-                if position_is_valid {
-                    possible_positions.push(Position::new(x, y));
-                }
+    /// plants an apple somewhere random that isn't a wall, a snek
+    /// or another apple. returns false if there was no room for it.
+    pub fn new_apple(&mut self) -> bool {
+        match self.choose_empty_position() {
+            Some(position) => {
+                let apple = self.pick_apple(position);
+                self.apples.push(apple);
+                self.occupancy.add(position);
+                self.publish(GameEvent::AppleSpawned { apple });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// somewhere random with nothing on it at all. it's picked the same
+    /// way as choosing from every empty cell in turn, so a seed plants
+    /// apples in the same places it always has.
+    fn choose_empty_position(&mut self) -> Option<Position> {
+        let empty = self.occupancy.count_empty();
+        if empty == 0 {
+            return None;
+        }
+        let n = match u32::try_from(empty) {
+            Ok(empty) => self.rng.gen_range(0..empty) as usize,
+            Err(_) => self.rng.gen_range(0..empty),
+        };
+        Some(self.occupancy.get_empty_position(n))
+    }
+
+    /// decides what kind of apple grows at `position`
+    fn pick_apple(&mut self, position: Position) -> Apple {
+        if !self.options.apple_varieties {
+            return Apple::new(position);
+        }
+        match self.rng.gen_range(0..20) {
+            0..=1 => Apple::golden(position),
+            2..=4 => Apple::poison(position),
+            5..=7 => Apple::timed(position, self.tick_count + TIMED_APPLE_TICKS),
+            _ => Apple::new(position),
+        }
+    }
+
+    /// the apple that's about to be eaten at `position`, if any
+    fn get_apple_at(&self, position: &Position) -> Option<&Apple> {
+        self.apples.iter().find(|a| a.will_be_eaten_by(position))
+    }
+
+    /// takes away any apples that rotted before they were eaten
+    fn rot_apples(&mut self) {
+        let tick = self.tick_count;
+        let apple_count = self.apples.len();
+        let occupancy = &mut self.occupancy;
+        self.apples.retain(|a| {
+            let rotten = a.has_expired(tick);
+            if rotten {
+                occupancy.remove(a.get_position());
+            }
+            !rotten
+        });
+        if self.apples.len() != apple_count {
+            tracing::info!("{} apples rotted away", apple_count - self.apples.len());
+            self.grow_apples();
+        }
+    }
+
+    /// every so often, grows a power-up if there isn't one on the board,
+    /// and takes away any that have waited too long to be picked up
+    fn grow_power_ups(&mut self) {
+        let tick = self.tick_count;
+        let occupancy = &mut self.occupancy;
+        self.power_ups.retain(|p| {
+            let expired = p.has_expired(tick);
+            if expired {
+                occupancy.remove(p.get_position());
+            }
+            !expired
+        });
+        if !self.options.power_ups
+            || !self.power_ups.is_empty()
+            || self.rng.gen_range(0..POWER_UP_CHANCE) != 0
+        {
+            return;
+        }
+        let kind = *PowerUpKind::ALL.choose(&mut self.rng).unwrap();
+        if let Some(position) = self.choose_empty_position() {
+            tracing::info!("a {} power-up grew at {:?}", kind.describe(), position);
+            self.power_ups
+                .push(PowerUp::new(position, kind, tick + POWER_UP_TICKS_ON_BOARD));
+            self.occupancy.add(position);
+        }
+    }
+
+    /// counts down every active power-up, then picks up the
+    /// power-up under each living snek's head if there is one
+    fn update_power_ups(&mut self) {
+        let mut changed = Vec::new();
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if !player.is_alive() {
+                continue;
+            }
+            let had_power_ups = !player.get_active_power_ups().is_empty();
+            player
+                .get_active_power_ups_mut()
+                .retain_mut(|p| p.wear_off());
+            let head = player.get_snek().get_head().get_position();
+            if let Some(i) = self.power_ups.iter().position(|p| p.get_position() == head) {
+                let kind = self.power_ups.remove(i).get_kind();
+                self.occupancy.remove(head);
+                tracing::info!("picked up a {} power-up", kind.describe());
+                let active_power_ups = player.get_active_power_ups_mut();
+                active_power_ups.retain(|p| p.get_kind() != kind);
+                active_power_ups.push(ActivePowerUp::new(kind));
             }
+            if had_power_ups || !player.get_active_power_ups().is_empty() {
+                changed.push(i);
+            }
+        }
+        for player in changed {
+            self.publish_power_ups(player);
         }
-        let mut rng = thread_rng();
-        self.apple = Apple::new(*possible_positions.choose(&mut rng).unwrap());
     }
 
+    /// the power-ups on the board waiting to be picked up
+    pub fn get_power_ups(&self) -> &[PowerUp] {
+        &self.power_ups
+    }
+
+    /// the power-ups player one has picked up, and how long each has left
+    pub fn get_active_power_ups(&self) -> &[ActivePowerUp] {
+        self.players[0].get_active_power_ups()
+    }
+
+    /// whether player one has a power-up
+    pub fn has_power_up(&self, kind: PowerUpKind) -> bool {
+        self.players[0].has_power_up(kind)
+    }
+
+    /// how much faster than usual the game should tick right now
+    pub fn get_speed_factor(&self) -> f64 {
+        self.players
+            .iter()
+            .filter(|p| p.is_alive())
+            .flat_map(|p| p.get_active_power_ups())
+            .map(|p| p.get_kind().get_speed_factor())
+            .product()
+    }
+
+    /// how long a tick lasts right now, given how far the game's [`Speed`](crate::core::Speed)
+    /// has got with the best score so far and any power-ups in play
+    pub fn get_tick_duration(&self) -> Duration {
+        let best_score = self.players.iter().map(|p| p.get_score()).max();
+        let speed = self.options.speed;
+        let ticks_per_second = speed.get_ticks_per_second(best_score.unwrap_or(0));
+        Duration::from_secs_f64(1.0 / (ticks_per_second * self.get_speed_factor()))
+    }
+
+    /// plants apples until there are as many as the options ask for,
+    /// or until there's no room left for any more
+    fn grow_apples(&mut self) {
+        while self.apples.len() < self.options.apples && self.new_apple() {}
+    }
+
+    /// moves the game forward one tick, returning what happened to player one
     #[tracing::instrument(level = "debug")]
     pub fn tick(&mut self) -> SlitherResult {
-        let slither_action = self.get_slither_action();
-        self.take_slither_action(&slither_action)
+        self.tick_all().swap_remove(0)
+    }
+
+    /// moves every snek forward at once, returning what happened to each
+    /// player in order. sneks that are already dead report how they died.
+    #[tracing::instrument(level = "debug")]
+    pub fn tick_all(&mut self) -> Vec<SlitherResult> {
+        self.publish(GameEvent::TickStarted {
+            tick: self.tick_count,
+        });
+        let slither_actions = self.plan_slither_actions();
+        let slither_results: Vec<SlitherResult> = slither_actions
+            .iter()
+            .enumerate()
+            .map(|(player, slither_action)| match slither_action {
+                Some(slither_action) => self.take_slither_action(player, slither_action),
+                None => SlitherResult::Died(self.players[player].get_death_cause().unwrap()),
+            })
+            .collect();
+        if let Some(replay) = &mut self.replay {
+            replay.record_outcomes(&slither_results);
+        }
+        self.finish_tick();
+        slither_results
+    }
+
+    /// moves every snek forward at once like [`State::tick_all`], without
+    /// copying every snek to say what happened to it. anything that needs
+    /// to know can look at the players afterwards. this is the one to use
+    /// in loops that play lots of games as fast as they can.
+    pub fn advance(&mut self) {
+        if self.replay.is_some() {
+            // replays need to know what happened to everyone
+            self.tick_all();
+            return;
+        }
+        self.publish(GameEvent::TickStarted {
+            tick: self.tick_count,
+        });
+        let slither_actions = self.plan_slither_actions();
+        for (player, slither_action) in slither_actions.iter().enumerate() {
+            if let Some(slither_action) = slither_action {
+                self.move_player(player, slither_action);
+            }
+        }
+        self.finish_tick();
+    }
+
+    /// works out where every living snek is going this tick
+    fn plan_slither_actions(&self) -> Vec<Option<SlitherAction>> {
+        let mut slither_actions: Vec<Option<SlitherAction>> = (0..self.players.len())
+            .map(|player| {
+                self.players[player]
+                    .is_alive()
+                    .then(|| self.get_slither_action(player))
+            })
+            .collect();
+        self.resolve_collisions(&mut slither_actions);
+        slither_actions
+    }
+
+    /// everything that happens once the sneks have moved
+    fn finish_tick(&mut self) {
+        let mut scored = Vec::new();
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if !player.is_alive() {
+                continue;
+            }
+            let score = player.get_score();
+            let heading = player.get_snek().get_head().get_direction();
+            player
+                .get_scoreboard_mut()
+                .tick(heading, &self.options.scoring);
+            if player.get_score() != score {
+                scored.push((i, player.get_score()));
+            }
+            player.take_queued_turn();
+        }
+        for (player, score) in scored {
+            self.publish(GameEvent::ScoreChanged { player, score });
+        }
+        if !self.is_game_over() {
+            self.grow_apples();
+        }
+        self.tick_count += 1;
+        if !self.is_game_over() {
+            self.update_power_ups();
+            self.rot_apples();
+            self.grow_power_ups();
+        }
+    }
+
+    /// a game on your own is over once the snek dies or eats the world.
+    /// a game against each other is over once there's at most one snek left.
+    pub fn is_game_over(&self) -> bool {
+        let alive = self.players.iter().filter(|p| p.is_alive()).count();
+        let ate_the_world = self
+            .players
+            .iter()
+            .any(|p| p.get_snek().count_segments() == self.occupancy.get_max_segments());
+        ate_the_world || alive == 0 || (self.players.len() > 1 && alive == 1)
+    }
+
+    /// drops a new baby snek somewhere with room to slither,
+    /// returning which player it belongs to. games that have
+    /// someone join partway through can't be replayed, so this
+    /// stops recording. returns `None` if there's no room left.
+    pub fn add_player(&mut self) -> Option<usize> {
+        let is_empty =
+            |position: Option<Position>| position.is_some_and(|p| self.occupancy.is_empty(&p));
+        // a couple of empty cells ahead, so it doesn't die straight away
+        let starts: Vec<(Position, Direction)> = self
+            .occupancy
+            .get_empty_positions()
+            .into_iter()
+            .flat_map(|position| Direction::ALL.map(|direction| (position, direction)))
+            .filter(|(position, direction)| {
+                let ahead = self.walls.neighbor(position, *direction);
+                is_empty(ahead)
+                    && is_empty(ahead.and_then(|ahead| self.walls.neighbor(&ahead, *direction)))
+            })
+            .collect();
+        let (position, direction) = *starts.choose(&mut self.rng)?;
+        let snek = Snek::baby_snek(position, direction);
+        self.occupancy.add_player();
+        self.occupancy.add_snek(self.players.len(), &snek);
+        self.players.push(Player::new(snek));
+        self.options.players = self.players.len();
+        self.stop_recording();
+        tracing::info!("player {} joined at {:?}", self.players.len(), position);
+        Some(self.players.len() - 1)
+    }
+
+    /// kills a player's snek on the spot, whatever it was doing.
+    /// like [`State::add_player`], this stops recording.
+    pub fn kill_player(&mut self, player: usize, cause: DeathCause) {
+        if self.players[player].is_alive() {
+            self.occupancy
+                .remove_snek(player, self.players[player].get_snek());
+            self.players[player].kill(cause);
+            self.publish(GameEvent::SnekDied { player, cause });
+            self.stop_recording();
+        }
+    }
+
+    /// the last snek standing in a game against each other, if there is one
+    pub fn get_winner(&self) -> Option<usize> {
+        if self.players.len() < 2 || !self.is_game_over() {
+            return None;
+        }
+        self.players.iter().position(|p| p.is_alive())
+    }
+
+    /// everything that will change once the game ticks, to [`State::restore`] later
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            players: self.players.clone(),
+            apples: self.apples.clone(),
+            power_ups: self.power_ups.clone(),
+            rng: self.rng.clone(),
+            tick_count: self.tick_count,
+            options: self.options,
+        }
+    }
+
+    /// puts the game back how it was when `snapshot` was taken.
+    /// a game that goes back in time can't be replayed, so this
+    /// stops recording.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        // only what's moved since needs counting again, unless someone joined
+        let same_players = self.players.len() == snapshot.players.len();
+        if same_players {
+            self.take_off_board();
+        }
+        self.players.clone_from(&snapshot.players);
+        self.apples.clone_from(&snapshot.apples);
+        self.power_ups.clone_from(&snapshot.power_ups);
+        if same_players {
+            self.put_on_board();
+        } else {
+            self.recount();
+        }
+        self.rng = snapshot.rng.clone();
+        self.tick_count = snapshot.tick_count;
+        self.options = snapshot.options;
+        self.stop_recording();
+        // anyone keeping score needs to know the points since then are gone
+        for player in 0..self.players.len() {
+            let score = self.players[player].get_score();
+            self.publish(GameEvent::ScoreChanged { player, score });
+            self.publish_power_ups(player);
+        }
+    }
+
+    /// a hash of everything that changes as the game is played. two
+    /// games on the same board that hash the same have played out
+    /// the same way, even in different processes.
+    pub fn get_hash(&self) -> u64 {
+        crate::core::snapshot::hash_game(
+            &self.players,
+            &self.apples,
+            &self.power_ups,
+            &self.rng,
+            self.tick_count,
+        )
+    }
+
+    /// how many times this game has ticked
+    pub fn get_tick(&self) -> usize {
+        self.tick_count
+    }
+
+    /// starts recording every turn and tick into a [`Replay`].
+    /// recording has to start before the first tick
+    /// so that playback can begin from the seed.
+    pub fn start_recording(&mut self) {
+        if self.tick_count != 0 {
+            panic!("recording must start before the first tick");
+        }
+        self.replay = Some(Replay::new(self));
+    }
+
+    /// a copy of the game to try moves out on, without the replay
+    pub(crate) fn fork(&self) -> State {
+        State {
+            walls: self.walls.clone(),
+            players: self.players.clone(),
+            apples: self.apples.clone(),
+            power_ups: self.power_ups.clone(),
+            occupancy: self.occupancy.clone(),
+            seed: self.seed,
+            rng: self.rng.clone(),
+            tick_count: self.tick_count,
+            replay: None,
+            options: self.options,
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// a copy of the game to show someone else, with a seed of its own so
+    /// they can't work out where the apples and power-ups will grow next
+    pub(crate) fn redact(&self) -> State {
+        let mut state = self.fork();
+        state.seed = thread_rng().gen();
+        state.rng = Pcg64::seed_from_u64(state.seed);
+        state
+    }
+
+    pub(crate) fn stop_recording(&mut self) {
+        self.replay = None;
+    }
+
+    pub fn get_replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
     }
 
+    /// the direction player one's snek will slither on the next tick
     pub fn get_direction(&self) -> Direction {
-        self.direction
+        self.players[0].get_direction()
     }
 
-    fn get_slither_action(&self) -> SlitherAction {
-        if let Some(potential_head) = self
-            .snek
-            .get_head()
-            .get_position()
-            .neighbor(self.get_direction())
-        {
+    pub fn get_options(&self) -> GameOptions {
+        self.options
+    }
+
+    /// the seed this game's randomness was derived from
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn get_slither_action(&self, player: usize) -> SlitherAction {
+        let direction = self.players[player].get_direction();
+        let snek = self.players[player].get_snek();
+        if let Some(potential_head) = self.next_head(player, direction) {
             if self.walls.collides_with(&potential_head) {
-                SlitherAction::Die {
-                    cause: DeathCause::Wall,
-                    direction: self.get_direction(),
-                }
-            } else if self.apple.will_be_eaten_by(&potential_head) {
-                if self.snek.will_i_run_into_myssself(&potential_head, true) {
+                self.hit_wall(player)
+            } else if let Some(apple) = self.get_apple_at(&potential_head) {
+                if let AppleKind::Poison = apple.get_kind() {
+                    if self.will_run_into_tail(player, &potential_head, false) {
+                        SlitherAction::Die {
+                            cause: DeathCause::Tail,
+                            direction,
+                        }
+                    } else if snek.count_segments() <= POISON_APPLE_SEGMENTS {
+                        SlitherAction::Die {
+                            cause: DeathCause::Poison,
+                            direction,
+                        }
+                    } else {
+                        SlitherAction::Shrink(direction)
+                    }
+                } else if self.will_run_into_tail(player, &potential_head, true) {
                     SlitherAction::Die {
                         cause: DeathCause::Tail,
-                        direction: self.get_direction(),
+                        direction,
                     }
                 } else {
-                    SlitherAction::Grow(self.get_direction())
+                    SlitherAction::Grow(direction)
                 }
-            } else if self.snek.will_i_run_into_myssself(&potential_head, false) {
+            } else if self.will_run_into_tail(player, &potential_head, false) {
                 SlitherAction::Die {
                     cause: DeathCause::Tail,
-                    direction: self.get_direction(),
+                    direction,
                 }
             } else {
-                SlitherAction::Slither(self.get_direction())
+                SlitherAction::Slither(direction)
             }
+        } else {
+            self.hit_wall(player)
+        }
+    }
+
+    /// a ghost can slither right through its own tail. the tip of the tail
+    /// moves out of the way as the head moves in, unless the snek is growing,
+    /// the same as when another snek runs into it.
+    fn will_run_into_tail(
+        &self,
+        player: usize,
+        potential_head: &Position,
+        will_grow: bool,
+    ) -> bool {
+        if self.players[player].has_power_up(PowerUpKind::Ghost) {
+            return false;
+        }
+        // everything but the head, and the tip of the tail unless growing
+        let snek = self.players[player].get_snek();
+        let is_at = |segment: &Segment| (segment.get_position() == *potential_head) as usize;
+        let mut tail = self.occupancy.count_segments(player, potential_head);
+        tail -= is_at(snek.get_head());
+        if !will_grow && snek.count_segments() > 1 {
+            tail -= is_at(snek.get_tail_end());
+        }
+        tail > 0
+    }
+
+    /// running into a wall is deadly, unless the snek has a shield
+    fn hit_wall(&self, player: usize) -> SlitherAction {
+        let player = &self.players[player];
+        if player.has_power_up(PowerUpKind::Shield) {
+            SlitherAction::Shield(player.get_direction())
         } else {
             SlitherAction::Die {
                 cause: DeathCause::Wall,
-                direction: self.get_direction(),
+                direction: player.get_direction(),
+            }
+        }
+    }
+
+    /// where a snek's head would end up if it moved in `direction`
+    fn next_head(&self, player: usize, direction: Direction) -> Option<Position> {
+        let head = self.players[player].get_snek().get_head().get_position();
+        self.walls.neighbor(&head, direction)
+    }
+
+    /// sneks run into each other after they've each worked out where
+    /// they're going. a snek that slithers into another snek's body dies,
+    /// and two sneks that meet head-on follow the [`HeadOnRule`].
+    fn resolve_collisions(&self, slither_actions: &mut [Option<SlitherAction>]) {
+        if self.players.len() < 2 {
+            return;
+        }
+        let moves: Vec<Option<Position>> = slither_actions
+            .iter()
+            .enumerate()
+            .map(|(player, slither_action)| match slither_action {
+                Some(
+                    SlitherAction::Grow(direction)
+                    | SlitherAction::Shrink(direction)
+                    | SlitherAction::Slither(direction),
+                ) => self.next_head(player, *direction),
+                _ => None,
+            })
+            .collect();
+        // whether a snek's body will be at `position` once everyone has moved, not counting
+        // new heads. the tip of a slithering snek's tail is gone.
+        let is_body_at = |player: usize, position: &Position| {
+            let segments = self.occupancy.count_segments(player, position);
+            match slither_actions[player] {
+                None => false,
+                Some(SlitherAction::Slither(_) | SlitherAction::Shrink(_)) => {
+                    let tail_end = self.players[player].get_snek().get_tail_end();
+                    segments > (tail_end.get_position() == *position) as usize
+                }
+                Some(_) => segments > 0,
+            }
+        };
+        let heads: Vec<Position> = self
+            .players
+            .iter()
+            .map(|p| p.get_snek().get_head().get_position())
+            .collect();
+        let mut deaths: Vec<Option<DeathCause>> = vec![None; self.players.len()];
+        for (player, new_head) in moves.iter().enumerate() {
+            let new_head = match new_head {
+                Some(new_head) => new_head,
+                None => continue,
+            };
+            for other in (0..self.players.len()).filter(|other| *other != player) {
+                let head_on = moves[other].is_some_and(|other_head| {
+                    other_head == *new_head
+                        || (other_head == heads[player] && *new_head == heads[other])
+                });
+                if head_on {
+                    let length = self.players[player].get_snek().count_segments();
+                    let other_length = self.players[other].get_snek().count_segments();
+                    let survives = match self.options.head_on {
+                        HeadOnRule::BothDie => false,
+                        HeadOnRule::LongerWins => length > other_length,
+                    };
+                    if !survives {
+                        deaths[player] = Some(DeathCause::HeadOn);
+                    }
+                } else if is_body_at(other, new_head) {
+                    deaths[player] = Some(DeathCause::OtherSnek);
+                }
+            }
+        }
+        for (player, death_cause) in deaths.into_iter().enumerate() {
+            if let Some(cause) = death_cause {
+                slither_actions[player] = Some(SlitherAction::Die {
+                    cause,
+                    direction: self.players[player].get_direction(),
+                });
             }
         }
     }
+
+    /// turns player one's snek
     #[tracing::instrument(level = "info")]
     pub fn turn_snek(&mut self, attempted_direction: Direction) -> bool {
-        // make sure the snek isn't trying to do a 180 back in on itself
-        let can_turn = !attempted_direction.is_on_a_dime(&self.snek.get_head().get_direction());
-        if can_turn {
-            // update the snek's _overall_ direction
-            // this will only update the heads direction in Snek::grow
-            // when we are actually ready to move
-            // this prevents someone from going right, then up, then left, causing death, all before the next tick even occurred
-            self.direction = attempted_direction;
+        self.turn_player(0, attempted_direction)
+    }
+
+    #[tracing::instrument(level = "info")]
+    pub fn turn_player(&mut self, player: usize, attempted_direction: Direction) -> bool {
+        if let Some(replay) = &mut self.replay {
+            replay.record_turn(self.tick_count, player, attempted_direction);
+        }
+        self.set_course(player, attempted_direction)
+    }
+
+    /// turns a snek without recording or logging it, for
+    /// bots trying out moves on a [`State::fork`]
+    pub(crate) fn set_course(
+        &mut self,
+        player_index: usize,
+        attempted_direction: Direction,
+    ) -> bool {
+        let player = &mut self.players[player_index];
+        // the snek's _overall_ direction only updates the head's direction in
+        // Snek::grow when we are actually ready to move. turns made before then
+        // queue up behind it, each checked against the turn before, so someone
+        // going right who quickly presses down then left goes down then left,
+        // instead of the left being lost or doubling the snek back on itself.
+        let turned = player.is_alive() && player.turn(attempted_direction);
+        let (player, direction) = (player_index, attempted_direction);
+        self.publish(match turned {
+            true => GameEvent::Turned { player, direction },
+            false => GameEvent::TurnRejected { player, direction },
+        });
+        turned
+    }
+
+    pub(crate) fn take_slither_action(
+        &mut self,
+        player: usize,
+        slither_action: &SlitherAction,
+    ) -> SlitherResult {
+        let mut slither_result = self.move_player(player, slither_action);
+        if let SlitherResult::Grew { segments, .. }
+        | SlitherResult::Shrank { segments, .. }
+        | SlitherResult::Slithered { segments, .. } = &mut slither_result
+        {
+            *segments = self.players[player].get_snek().get_segments();
         }
-        can_turn
+        slither_result
     }
 
-    pub(crate) fn take_slither_action(&mut self, slither_action: &SlitherAction) -> SlitherResult {
+    /// does what a snek decided to do this tick. the result's
+    /// `segments` are left empty so they don't have to be copied.
+    fn move_player(&mut self, player: usize, slither_action: &SlitherAction) -> SlitherResult {
         match slither_action {
             SlitherAction::Die { cause, direction } => {
-                self.take_slither_action(&SlitherAction::Slither(*direction));
-                self.snek.kill();
+                // there may be nowhere to slither if the snek ran off the edge of the board
+                if let Some(new_head) = self.next_head(player, *direction) {
+                    if let DeathCause::Poison = cause {
+                        self.take_apple(player, &new_head);
+                    }
+                    self.move_player(player, &SlitherAction::Slither(*direction));
+                }
+                self.occupancy
+                    .remove_snek(player, self.players[player].get_snek());
+                self.players[player].kill(*cause);
+                self.publish(GameEvent::SnekDied {
+                    player,
+                    cause: *cause,
+                });
                 tracing::info!("snek died");
                 SlitherResult::Died(*cause)
             }
             SlitherAction::Grow(direction) => {
-                let slime_trail = self.snek.get_segments().first().unwrap().get_position();
-                self.snek.grow(direction);
-                self.scoreboard.increment_score();
-                tracing::info!("ate an apple, new score: {}", self.scoreboard.get_score());
-                if self.snek.count_segments() == self.walls.get_max_segments() {
+                let new_head = self.next_head(player, *direction).unwrap();
+                let eaten = self.take_apple(player, &new_head);
+                let max_segments = self.occupancy.get_max_segments();
+                let index = player;
+                let player = &mut self.players[index];
+                let slime_trail = player.get_snek().get_tail_end().get_position();
+                let score = player.get_score();
+                player.get_snek_mut().grow_to(direction, new_head);
+                self.occupancy.add_segment(index, new_head);
+                player.get_scoreboard_mut().eat(
+                    eaten.get_points(),
+                    &self.options.scoring,
+                    max_segments,
+                );
+                tracing::info!(
+                    "ate a {}, new score: {}",
+                    eaten.describe(),
+                    player.get_score()
+                );
+                let scored = player.get_score() != score;
+                let score = player.get_score();
+                let ate_the_world = player.get_snek().count_segments() == max_segments;
+                if scored {
+                    self.publish(GameEvent::ScoreChanged {
+                        player: index,
+                        score,
+                    });
+                }
+                if ate_the_world {
+                    self.publish(GameEvent::AteTheWorld { player: index });
                     SlitherResult::AteTheWorld
                 } else {
-                    self.new_apple();
                     SlitherResult::Grew {
                         direction: *direction,
-                        segments: self.snek.get_segments(),
+                        segments: Vec::new(),
                         slime_trail,
+                        eaten,
                     }
                 }
             }
+            SlitherAction::Shrink(direction) => {
+                let new_head = self.next_head(player, *direction).unwrap();
+                let eaten = self.take_apple(player, &new_head);
+                let snek = self.players[player].get_snek_mut();
+                let mut slime_trail = vec![snek.get_tail_end().get_position()];
+                snek.slither_to(direction, new_head);
+                slime_trail.extend(snek.shrink(POISON_APPLE_SEGMENTS));
+                self.occupancy.add_segment(player, new_head);
+                for position in &slime_trail {
+                    self.occupancy.remove_segment(player, *position);
+                }
+                tracing::info!(
+                    "ate a {}, shrank to {}",
+                    eaten.describe(),
+                    snek.count_segments()
+                );
+                SlitherResult::Shrank {
+                    direction: *direction,
+                    segments: Vec::new(),
+                    slime_trail,
+                }
+            }
+            SlitherAction::Shield(direction) => {
+                self.players[player]
+                    .get_active_power_ups_mut()
+                    .retain(|p| p.get_kind() != PowerUpKind::Shield);
+                self.publish_power_ups(player);
+                tracing::info!("the shield stopped the snek running into a wall");
+                SlitherResult::Shielded {
+                    direction: *direction,
+                }
+            }
             SlitherAction::Slither(direction) => {
-                let slime_trail = self.snek.get_segments().first().unwrap().get_position();
-                self.snek.slither(direction);
+                let new_head = self.next_head(player, *direction).unwrap();
+                let snek = self.players[player].get_snek_mut();
+                let slime_trail = snek.get_tail_end().get_position();
+                snek.slither_to(direction, new_head);
+                self.occupancy.remove_segment(player, slime_trail);
+                self.occupancy.add_segment(player, new_head);
                 tracing::info!("slithered {:?}", direction);
                 SlitherResult::Slithered {
                     direction: *direction,
-                    segments: self.get_snek().get_segments(),
+                    segments: Vec::new(),
                     slime_trail,
                 }
             }
         }
     }
 
+    /// picks the apple at `position` off the board for `player`, returning what kind it was
+    fn take_apple(&mut self, player: usize, position: &Position) -> AppleKind {
+        let eaten = self.get_apple_at(position).copied();
+        let occupancy = &mut self.occupancy;
+        self.apples.retain(|a| {
+            let will_be_eaten = a.will_be_eaten_by(position);
+            if will_be_eaten {
+                occupancy.remove(a.get_position());
+            }
+            !will_be_eaten
+        });
+        match eaten {
+            Some(apple) => {
+                self.publish(GameEvent::AppleEaten { player, apple });
+                apple.get_kind()
+            }
+            None => AppleKind::default(),
+        }
+    }
+
+    /// starts listening to everything that happens in the game from now on.
+    /// copies of the game, and snapshots of it, don't say anything.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        self.subscribers.subscribe()
+    }
+
+    /// sends everything that happens in the game from now on to `subscriber`,
+    /// for anyone who wants to hear about more than one game on one channel
+    pub fn add_subscriber(&mut self, subscriber: Sender<GameEvent>) {
+        self.subscribers.add(subscriber);
+    }
+
+    fn publish(&mut self, event: GameEvent) {
+        self.subscribers.publish(event);
+    }
+
+    fn publish_power_ups(&mut self, player: usize) {
+        if !self.subscribers.is_empty() {
+            let power_ups = self.players[player].get_active_power_ups().to_vec();
+            self.publish(GameEvent::PowerUpsChanged { player, power_ups });
+        }
+    }
+
+    /// writes the whole game to disk, including the
+    /// rng so a resumed game keeps playing out the same way
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let saved_game = SavedGame {
+            version: SAVE_VERSION,
+            state: self,
+        };
+        serde_json::to_writer(writer, &saved_game).map_err(io::Error::from)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<State> {
+        let reader = BufReader::new(File::open(path)?);
+        let saved_game: SavedGame<State> =
+            serde_json::from_reader(reader).map_err(io::Error::from)?;
+        if saved_game.version > SAVE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "game was saved with format version {}, but this build only understands up to version {}",
+                    saved_game.version, SAVE_VERSION
+                ),
+            ));
+        }
+        Ok(saved_game.state)
+    }
+
+    /// player one's snek
     #[tracing::instrument(level = "trace")]
     pub fn get_snek(&self) -> &Snek {
-        &self.snek
+        self.players[0].get_snek()
     }
 
+    /// player one's score
     #[tracing::instrument(level = "trace")]
     pub fn get_score(&self) -> usize {
-        self.scoreboard.get_score()
+        self.players[0].get_score()
+    }
+
+    /// everyone playing, player one first
+    pub fn get_players(&self) -> &[Player] {
+        &self.players
     }
 
+    /// every apple on the board, in the order they were planted
     #[tracing::instrument(level = "trace")]
-    pub fn get_apple(&self) -> &Apple {
-        &self.apple
+    pub fn get_apples(&self) -> &[Apple] {
+        &self.apples
     }
 
     #[tracing::instrument(level = "trace")]
@@ -179,8 +1066,58 @@ impl State {
         &self.walls
     }
 
-    pub fn get_walls_mut(&mut self) -> &mut Walls {
-        &mut self.walls
+    /// blocks off a cell partway through a game. everything on the
+    /// board is counted up again, so it's not something to do every tick.
+    pub fn add_wall(&mut self, position: Position) {
+        self.walls.add_wall(position);
+        self.recount();
+    }
+
+    pub fn remove_wall(&mut self, position: Position) {
+        self.walls.remove_wall(position);
+        self.recount();
+    }
+
+    /// takes every living snek, apple and power-up out of the
+    /// occupancy, leaving only the walls
+    fn take_off_board(&mut self) {
+        for (player, p) in self.players.iter().enumerate() {
+            if p.is_alive() {
+                self.occupancy.remove_snek(player, p.get_snek());
+            }
+        }
+        let items = self.apples.iter().map(|a| a.get_position());
+        for position in items.chain(self.power_ups.iter().map(|p| p.get_position())) {
+            self.occupancy.remove(position);
+        }
+    }
+
+    /// puts every living snek, apple and power-up back in the occupancy
+    fn put_on_board(&mut self) {
+        for (player, p) in self.players.iter().enumerate() {
+            if p.is_alive() {
+                self.occupancy.add_snek(player, p.get_snek());
+            }
+        }
+        let items = self.apples.iter().map(|a| a.get_position());
+        for position in items.chain(self.power_ups.iter().map(|p| p.get_position())) {
+            self.occupancy.add(position);
+        }
+    }
+
+    /// works out what's in every cell from scratch
+    fn recount(&mut self) {
+        self.occupancy = Occupancy::new(&self.walls, &self.players, &self.apples, &self.power_ups);
+    }
+
+    /// whether `player`'s snek has a segment at `position`. dead sneks aren't anywhere.
+    pub fn is_player_at(&self, player: usize, position: &Position) -> bool {
+        self.occupancy.count_segments(player, position) > 0
+    }
+
+    /// whether any living snek has a segment at `position`
+    pub fn is_snek_at(&self, position: &Position) -> bool {
+        (0..self.players.len()).any(|player| self.is_player_at(player, position))
     }
 
     #[tracing::instrument(level = "trace")]
@@ -188,16 +1125,29 @@ impl State {
         self.walls.collides_with(position)
     }
 
+    /// replaces every apple on the board with one at (x, y)
     #[cfg(test)]
     pub(crate) fn plant_apple(&mut self, x: usize, y: usize) {
-        self.apple = Apple::new(Position::new(x, y));
+        self.apples = vec![Apple::new(Position::new(x, y))];
+        self.recount();
+    }
+
+    /// replaces every apple on the board with one somewhere random
+    #[cfg(test)]
+    pub(crate) fn replant_apple(&mut self) {
+        self.apples.clear();
+        self.recount();
+        self.new_apple();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::{DeathCause, Position};
+    use crate::core::{
+        Autopilot, Controller, DeathCause, Position, GOLDEN_APPLE_POINTS, MAX_QUEUED_TURNS,
+        TIMED_APPLE_POINTS,
+    };
 
     #[test]
     fn it_can_eat_an_apple() {
@@ -243,14 +1193,47 @@ mod tests {
         // turn down
         assert!(state.turn_snek(Direction::Down));
 
-        // make sure we cannot turn left before the next tick (into itself)
-        assert!(!state.turn_snek(Direction::Left));
+        // turning left before the next tick would be into itself,
+        // so it waits until the snek is going down
+        assert!(state.turn_snek(Direction::Left));
 
-        // tick the game forward, we should continue down since we couldn't turn left earlier
+        // tick the game forward, we should go down first, then left
         assert_eq!(state.tick().get_direction().unwrap(), Direction::Down);
+        assert_eq!(state.tick().get_direction().unwrap(), Direction::Left);
 
-        // now we can turn left
-        assert!(state.turn_snek(Direction::Left));
+        // but we still can't turn straight back on ourselves
+        assert!(!state.turn_snek(Direction::Right));
+        assert!(state.turn_snek(Direction::Up));
+        assert!(!state.turn_snek(Direction::Down));
+    }
+
+    #[test]
+    fn quick_turns_queue_up_one_per_tick() {
+        let mut state = State::new(20, 20);
+        assert_eq!(state.get_direction(), Direction::Right);
+        assert!(state.turn_snek(Direction::Down));
+        assert!(state.turn_snek(Direction::Right));
+        assert!(state.turn_snek(Direction::Down));
+        assert!(state.turn_snek(Direction::Right));
+        assert_eq!(
+            state.get_players()[0].get_queued_turns().len(),
+            MAX_QUEUED_TURNS
+        );
+        // the queue is full
+        assert!(!state.turn_snek(Direction::Up));
+        let directions: Vec<Direction> = (0..5)
+            .map(|_| state.tick().get_direction().unwrap())
+            .collect();
+        assert_eq!(
+            directions,
+            [
+                Direction::Down,
+                Direction::Right,
+                Direction::Down,
+                Direction::Right,
+                Direction::Right
+            ]
+        );
     }
 
     #[test]
@@ -383,28 +1366,779 @@ mod tests {
                 .get_position(),
             Position::new(1, 1)
         );
+        // growing keeps the tip of the tail where it is
+        state.plant_apple(1, 1);
         assert_eq!(state.tick(), SlitherResult::Died(DeathCause::Tail));
         assert!(!state.get_snek().is_alive())
     }
 
-    fn direction_state(direction: Direction) -> State {
-        let mut state = State::new(10, 10);
-        state.direction = direction;
-        state.snek.get_head_mut().set_direction(&direction);
-        state
+    #[test]
+    fn boards_need_room_to_play_on() {
+        assert!(State::check_board_size(4, 4).is_ok());
+        assert!(State::check_board_size(3, 6).is_ok());
+        assert!(State::check_board_size(3, 3).is_err());
+        assert!(State::check_board_size(1, 20).is_err());
+        assert!(State::check_board_size(2, 8).is_err());
+        State::with_options(3, 6, 0, GameOptions::two_player());
     }
 
     #[test]
-    fn snek_can_turn_from_up_to_left() {
-        let mut state = direction_state(Direction::Up);
-        assert_eq!(state.get_direction(), Direction::Up);
-        assert!(state.turn_snek(Direction::Left));
-        state.tick();
-        assert_eq!(state.get_direction(), Direction::Left);
+    fn it_reports_its_seed() {
+        let state = State::with_seed(10, 10, 42);
+        assert_eq!(state.get_seed(), 42);
     }
 
     #[test]
-    fn snek_can_turn_from_up_to_right() {
+    fn it_places_apples_deterministically_from_a_seed() {
+        let mut first = State::with_seed(20, 20, 1234);
+        let mut second = State::with_seed(20, 20, 1234);
+        assert_eq!(first.get_apples(), second.get_apples());
+        for _ in 0..50 {
+            first.replant_apple();
+            second.replant_apple();
+            assert_eq!(first.get_apples(), second.get_apples());
+        }
+    }
+
+    #[test]
+    fn it_plays_out_identically_from_a_seed() {
+        let turns = [
+            Direction::Down,
+            Direction::Right,
+            Direction::Up,
+            Direction::Right,
+        ];
+        let play = |seed| {
+            let mut state = State::with_seed(12, 12, seed);
+            let mut results = Vec::new();
+            for turn in turns.iter().cycle().take(40) {
+                state.turn_snek(*turn);
+                let result = state.tick();
+                let died = matches!(result, SlitherResult::Died(_));
+                results.push((result, state.get_apples()[0].get_position()));
+                if died {
+                    break;
+                }
+            }
+            results
+        };
+        assert_eq!(play(99), play(99));
+    }
+
+    #[test]
+    fn different_seeds_place_different_apples() {
+        let apples = |seed| {
+            let mut state = State::with_seed(30, 30, seed);
+            (0..10)
+                .map(|_| {
+                    state.replant_apple();
+                    state.get_apples()[0].get_position()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(apples(1), apples(2));
+    }
+
+    #[test]
+    fn it_resumes_a_saved_game_exactly() {
+        let mut state = State::with_seed(10, 10, 8);
+        state.start_recording();
+        state.plant_apple(2, 1);
+        state.tick();
+        assert!(state.turn_snek(Direction::Down));
+        let path = std::env::temp_dir().join(format!("snek-save-{}.json", std::process::id()));
+        state.save(&path).unwrap();
+        let mut resumed = State::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.get_seed(), state.get_seed());
+        assert_eq!(resumed.get_tick(), state.get_tick());
+        assert_eq!(resumed.get_score(), 1);
+        assert_eq!(resumed.get_direction(), Direction::Down);
+        assert_eq!(resumed.get_apples(), state.get_apples());
+        assert_eq!(
+            resumed.get_snek().get_segments(),
+            state.get_snek().get_segments()
+        );
+        // the rng picks up where it left off
+        for _ in 0..10 {
+            state.replant_apple();
+            resumed.replant_apple();
+            assert_eq!(resumed.get_apples(), state.get_apples());
+        }
+        assert_eq!(resumed.tick(), state.tick());
+        assert_eq!(resumed.get_replay(), state.get_replay());
+    }
+
+    #[test]
+    fn it_wraps_around_a_toroidal_board() {
+        let mut state = State::with_options(4, 4, 0, GameOptions::wraparound());
+        state.plant_apple(3, 3);
+        // the snek starts at (1, 1) heading right
+        assert_eq!(state.tick().get_direction(), Some(Direction::Right));
+        assert_eq!(state.tick().get_direction(), Some(Direction::Right));
+        assert_eq!(
+            state.get_snek().get_head().get_position(),
+            Position::new(3, 1)
+        );
+        // off the right edge and back in on the left
+        assert_eq!(state.tick().get_direction(), Some(Direction::Right));
+        assert_eq!(
+            state.get_snek().get_head().get_position(),
+            Position::new(0, 1)
+        );
+        // off the top edge and back in at the bottom
+        assert!(state.turn_snek(Direction::Up));
+        state.tick();
+        state.tick();
+        assert_eq!(
+            state.get_snek().get_head().get_position(),
+            Position::new(0, 3)
+        );
+        assert!(state.get_snek().is_alive());
+    }
+
+    #[test]
+    fn a_snek_can_follow_the_tip_of_its_own_tail() {
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 2);
+        let mut state = State::from_parts(
+            Walls::new(6, 6),
+            vec![snek],
+            vec![Apple::new(Position::new(2, 2))],
+            0,
+            GameOptions::default(),
+        );
+        state.turn_snek(Direction::Down);
+        state.tick();
+        state.plant_apple(1, 2);
+        state.turn_snek(Direction::Left);
+        state.tick();
+        assert_eq!(state.get_snek().count_segments(), 4);
+        state.turn_snek(Direction::Up);
+        // growing keeps the tip of the tail at (1, 1) where it is
+        let mut growing = state.clone();
+        growing.plant_apple(1, 1);
+        assert_eq!(growing.tick(), SlitherResult::Died(DeathCause::Tail));
+        // but slithering moves it out of the way
+        state.plant_apple(4, 4);
+        assert!(matches!(state.tick(), SlitherResult::Slithered { .. }));
+        assert_eq!(state.get_snek().get_head().get_position(), Position::new(1, 1));
+    }
+
+    #[test]
+    fn it_can_still_die_by_hitting_tail_on_a_toroidal_board() {
+        let mut state = State::with_options(4, 4, 0, GameOptions::wraparound());
+        // eat along the whole row and one more, then wrap around into the body
+        for x in [2, 3, 0] {
+            state.plant_apple(x, 1);
+            state.tick();
+        }
+        state.turn_snek(Direction::Down);
+        state.plant_apple(0, 2);
+        state.tick();
+        assert_eq!(state.get_snek().count_segments(), 5);
+        state.plant_apple(2, 3);
+        state.turn_snek(Direction::Left);
+        assert!(matches!(state.tick(), SlitherResult::Slithered { .. }));
+        assert_eq!(state.get_snek().get_head().get_position(), Position::new(3, 2));
+        state.turn_snek(Direction::Up);
+        assert_eq!(state.tick(), SlitherResult::Died(DeathCause::Tail));
+    }
+
+    #[test]
+    fn it_spawns_apples_anywhere_on_a_toroidal_board() {
+        let mut state = State::with_options(4, 4, 5, GameOptions::wraparound());
+        let mut on_edge = false;
+        for _ in 0..100 {
+            state.replant_apple();
+            let (x, y) = state.get_apples()[0].get_position().get_coordinates();
+            on_edge |= x == 0 || y == 0 || x == 3 || y == 3;
+        }
+        assert!(on_edge);
+    }
+
+    #[test]
+    fn it_can_die_by_hitting_an_interior_wall() {
+        let mut state = State::new(10, 10);
+        state.add_wall(Position::new(3, 1));
+        assert_eq!(state.tick().get_direction(), Some(Direction::Right));
+        assert_eq!(state.tick(), SlitherResult::Died(DeathCause::Wall));
+        assert!(!state.get_snek().is_alive())
+    }
+
+    #[test]
+    fn it_never_spawns_apples_in_interior_walls() {
+        let mut state = State::with_seed(5, 5, 17);
+        // wall off everything but a couple of cells
+        for x in 1..4 {
+            for y in 1..4 {
+                state.add_wall(Position::new(x, y));
+            }
+        }
+        state.remove_wall(Position::new(1, 1));
+        state.remove_wall(Position::new(2, 1));
+        for _ in 0..20 {
+            state.replant_apple();
+            assert_eq!(state.get_apples()[0].get_position(), Position::new(2, 1));
+        }
+    }
+
+    #[test]
+    fn it_keeps_several_apples_on_the_board() {
+        let options = GameOptions {
+            apples: 5,
+            ..GameOptions::default()
+        };
+        let state = State::with_options(10, 10, 3, options);
+        let apples = state.get_apples();
+        assert_eq!(apples.len(), 5);
+        for (i, apple) in apples.iter().enumerate() {
+            assert!(state.get_walls().is_position_inside(&apple.get_position()));
+            assert_ne!(
+                apple.get_position(),
+                state.get_snek().get_head().get_position()
+            );
+            assert!(apples[i + 1..]
+                .iter()
+                .all(|other| other.get_position() != apple.get_position()));
+        }
+    }
+
+    #[test]
+    fn each_apple_respawns_on_its_own() {
+        let options = GameOptions {
+            apples: 2,
+            ..GameOptions::default()
+        };
+        let far_apple = Apple::new(Position::new(5, 5));
+        let apples = vec![Apple::new(Position::new(2, 1)), far_apple];
+        let mut state = State::from_parts(
+            Walls::new(10, 10),
+            vec![Snek::default()],
+            apples,
+            4,
+            options,
+        );
+        assert!(matches!(state.tick(), SlitherResult::Grew { .. }));
+        let apples = state.get_apples();
+        assert_eq!(apples.len(), 2);
+        assert_eq!(apples[0], far_apple);
+        assert_ne!(apples[1].get_position(), Position::new(2, 1));
+    }
+
+    #[test]
+    fn it_stops_planting_apples_when_the_board_is_full() {
+        let options = GameOptions {
+            apples: 100,
+            ..GameOptions::default()
+        };
+        let state = State::with_options(4, 4, 1, options);
+        // a 4x4 board has 4 free cells and the snek is on one of them
+        assert_eq!(state.get_apples().len(), 3);
+    }
+
+    #[test]
+    fn it_loads_games_saved_with_a_single_apple() {
+        let state = State::with_seed(10, 10, 6);
+        let apple = serde_json::to_string(&state.get_apples()[0]).unwrap();
+        let saved_game = serde_json::to_string(&state).unwrap().replace(
+            &format!("\"apples\":[{}]", apple),
+            &format!("\"apple\":{}", apple),
+        );
+        assert!(!saved_game.contains("\"apples\":["));
+        let loaded: State = serde_json::from_str(&saved_game).unwrap();
+        assert_eq!(loaded, state);
+    }
+
+    fn state_with_apple(snek_length: usize, apple: Apple) -> State {
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, snek_length);
+        State::from_parts(
+            Walls::new(12, 12),
+            vec![snek],
+            vec![apple],
+            2,
+            GameOptions::default(),
+        )
+    }
+
+    #[test]
+    fn golden_apples_are_worth_bonus_points() {
+        let mut state = state_with_apple(1, Apple::golden(Position::new(2, 1)));
+        assert!(matches!(
+            state.tick(),
+            SlitherResult::Grew {
+                eaten: AppleKind::Golden,
+                ..
+            }
+        ));
+        assert_eq!(state.get_score(), GOLDEN_APPLE_POINTS);
+        assert_eq!(state.get_snek().count_segments(), 2);
+    }
+
+    #[test]
+    fn poison_apples_shrink_the_snek() {
+        let mut state = state_with_apple(5, Apple::poison(Position::new(6, 1)));
+        match state.tick() {
+            SlitherResult::Shrank {
+                segments,
+                slime_trail,
+                ..
+            } => {
+                assert_eq!(segments.len(), 5 - POISON_APPLE_SEGMENTS);
+                assert_eq!(
+                    slime_trail,
+                    vec![
+                        Position::new(1, 1),
+                        Position::new(2, 1),
+                        Position::new(3, 1),
+                        Position::new(4, 1)
+                    ]
+                );
+                assert_eq!(segments.last().unwrap().get_position(), Position::new(6, 1));
+            }
+            result => panic!("expected the snek to shrink, but {}", result.describe()),
+        }
+        assert_eq!(state.get_score(), 0);
+        assert_eq!(state.get_apples().len(), 1);
+        assert_ne!(state.get_apples()[0].get_position(), Position::new(6, 1));
+    }
+
+    #[test]
+    fn poison_apples_can_kill_a_short_snek() {
+        let mut state = state_with_apple(POISON_APPLE_SEGMENTS, Apple::poison(Position::new(4, 1)));
+        assert_eq!(state.tick(), SlitherResult::Died(DeathCause::Poison));
+        assert!(state.get_snek().is_dead());
+    }
+
+    #[test]
+    fn timed_apples_rot_away() {
+        let mut state = state_with_apple(1, Apple::timed(Position::new(10, 10), 3));
+        state.tick();
+        state.tick();
+        assert_eq!(state.get_apples()[0].get_kind(), AppleKind::Timed);
+        state.tick();
+        // a plain apple grows in its place
+        assert_eq!(state.get_apples().len(), 1);
+        assert_eq!(state.get_apples()[0].get_kind(), AppleKind::Plain);
+    }
+
+    #[test]
+    fn timed_apples_score_if_eaten_in_time() {
+        let mut state = state_with_apple(1, Apple::timed(Position::new(2, 1), 1));
+        assert!(matches!(
+            state.tick(),
+            SlitherResult::Grew {
+                eaten: AppleKind::Timed,
+                ..
+            }
+        ));
+        assert_eq!(state.get_score(), TIMED_APPLE_POINTS);
+    }
+
+    #[test]
+    fn an_orchard_grows_every_kind_of_apple() {
+        let mut state = State::with_options(20, 20, 9, GameOptions::orchard());
+        let mut kinds = Vec::new();
+        for _ in 0..200 {
+            state.replant_apple();
+            let kind = state.get_apples()[0].get_kind();
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        assert_eq!(kinds.len(), 4);
+    }
+
+    #[test]
+    fn ghosts_slither_through_their_own_tail() {
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 5);
+        let mut state = State::from_parts(
+            Walls::new(12, 12),
+            vec![snek],
+            vec![Apple::new(Position::new(10, 10))],
+            1,
+            GameOptions::default(),
+        );
+        state.players[0]
+            .get_active_power_ups_mut()
+            .push(ActivePowerUp::new(PowerUpKind::Ghost));
+        for direction in [Direction::Down, Direction::Left, Direction::Up] {
+            state.turn_snek(direction);
+            assert!(matches!(state.tick(), SlitherResult::Slithered { .. }));
+        }
+        // the head is right on top of the tail
+        assert_eq!(
+            state.get_snek().get_head().get_position(),
+            Position::new(4, 1)
+        );
+        assert!(state.get_snek().is_alive());
+    }
+
+    #[test]
+    fn shields_absorb_one_wall_hit() {
+        let mut state = State::new(10, 10);
+        state.players[0]
+            .get_active_power_ups_mut()
+            .push(ActivePowerUp::new(PowerUpKind::Shield));
+        state.turn_snek(Direction::Up);
+        assert_eq!(
+            state.tick(),
+            SlitherResult::Shielded {
+                direction: Direction::Up
+            }
+        );
+        assert_eq!(
+            state.get_snek().get_head().get_position(),
+            Position::new(1, 1)
+        );
+        assert!(!state.has_power_up(PowerUpKind::Shield));
+        assert_eq!(state.tick(), SlitherResult::Died(DeathCause::Wall));
+    }
+
+    #[test]
+    fn the_snek_picks_up_power_ups() {
+        let mut state = State::new(40, 40);
+        state.plant_apple(38, 38);
+        state.power_ups.push(PowerUp::new(
+            Position::new(2, 1),
+            PowerUpKind::SlowMotion,
+            10,
+        ));
+        state.recount();
+        assert_eq!(state.get_speed_factor(), 1.0);
+        state.tick();
+        assert!(state.get_power_ups().is_empty());
+        assert!(state.has_power_up(PowerUpKind::SlowMotion));
+        assert_eq!(state.get_speed_factor(), 0.5);
+        let duration = PowerUpKind::SlowMotion.get_duration();
+        assert_eq!(state.get_active_power_ups()[0].get_ticks_left(), duration);
+        state.turn_snek(Direction::Down);
+        for _ in 1..duration {
+            state.tick();
+        }
+        assert_eq!(state.get_active_power_ups()[0].get_ticks_left(), 1);
+        state.tick();
+        assert!(state.get_active_power_ups().is_empty());
+    }
+
+    #[test]
+    fn speedy_games_tick_faster_as_the_snek_scores() {
+        let mut state = State::with_options(10, 10, 0, GameOptions::speedy());
+        let start = state.get_tick_duration();
+        assert_eq!(start, Duration::from_millis(200));
+        state.players[0].get_scoreboard_mut().add_points(4);
+        assert_eq!(
+            state.get_tick_duration(),
+            Duration::from_secs_f64(1.0 / 6.0)
+        );
+        state.players[0].get_scoreboard_mut().add_points(1_000);
+        assert_eq!(state.get_tick_duration(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn arcade_scores_add_up() {
+        let mut state = State::with_options(10, 10, 3, GameOptions::arcade());
+        let events = state.subscribe();
+        let mut autopilot = Autopilot::new();
+        while !state.is_game_over() && state.get_tick() < 400 {
+            if let Some(direction) = autopilot.steer(&state, 0) {
+                state.turn_snek(direction);
+            }
+            state.tick();
+        }
+        let breakdown = state.get_players()[0].get_scoreboard().get_breakdown();
+        assert!(breakdown.survival > 0);
+        assert!(breakdown.board_size < 0, "{:?}", breakdown);
+        let total = (breakdown.apples + breakdown.combos + breakdown.survival) as isize
+            + breakdown.board_size
+            - breakdown.idling as isize;
+        assert_eq!(total as usize, state.get_score());
+        let last_score = events
+            .try_iter()
+            .filter_map(|event| match event {
+                GameEvent::ScoreChanged { score, .. } => Some(score),
+                _ => None,
+            })
+            .last();
+        assert_eq!(last_score, Some(state.get_score()));
+    }
+
+    #[test]
+    fn power_ups_only_grow_when_the_options_allow() {
+        let count_power_ups = |options| {
+            let mut state = State::with_options(30, 30, 12, options);
+            let mut grown = 0;
+            for _ in 0..400 {
+                state.power_ups.clear();
+                state.recount();
+                state.grow_power_ups();
+                grown += state.get_power_ups().len();
+            }
+            grown
+        };
+        assert_eq!(count_power_ups(GameOptions::default()), 0);
+        assert!(count_power_ups(GameOptions::power_ups()) > 0);
+    }
+
+    fn versus(sneks: Vec<Snek>, head_on: HeadOnRule) -> State {
+        State::from_parts(
+            Walls::new(12, 12),
+            sneks,
+            vec![Apple::new(Position::new(10, 10))],
+            9,
+            GameOptions {
+                head_on,
+                ..GameOptions::two_player()
+            },
+        )
+    }
+
+    #[test]
+    fn two_players_start_in_opposite_corners() {
+        let mut state = State::with_options(12, 12, 3, GameOptions::two_player());
+        let players = state.get_players();
+        assert_eq!(players.len(), 2);
+        assert_eq!(
+            players[0].get_snek().get_head().get_position(),
+            Position::new(1, 1)
+        );
+        assert_eq!(
+            players[1].get_snek().get_head().get_position(),
+            Position::new(10, 10)
+        );
+        assert_eq!(players[1].get_direction(), Direction::Left);
+        assert_eq!(state.get_apples().len(), 2);
+        assert_eq!(state.tick_all().len(), 2);
+        assert!(!state.is_game_over());
+    }
+
+    #[test]
+    fn sneks_the_same_length_both_die_head_on() {
+        let mut state = versus(
+            vec![
+                Snek::baby_snek(Position::new(3, 5), Direction::Right),
+                Snek::baby_snek(Position::new(5, 5), Direction::Left),
+            ],
+            HeadOnRule::LongerWins,
+        );
+        assert_eq!(
+            state.tick_all(),
+            vec![
+                SlitherResult::Died(DeathCause::HeadOn),
+                SlitherResult::Died(DeathCause::HeadOn)
+            ]
+        );
+        assert!(state.is_game_over());
+        assert_eq!(state.get_winner(), None);
+    }
+
+    #[test]
+    fn the_longer_snek_wins_head_on() {
+        let mut state = versus(
+            vec![
+                Snek::line_snek(Position::new(1, 5), Direction::Right, 3),
+                Snek::baby_snek(Position::new(5, 5), Direction::Left),
+            ],
+            HeadOnRule::LongerWins,
+        );
+        let slither_results = state.tick_all();
+        assert!(matches!(
+            slither_results[0],
+            SlitherResult::Slithered { .. }
+        ));
+        assert_eq!(slither_results[1], SlitherResult::Died(DeathCause::HeadOn));
+        assert_eq!(state.get_winner(), Some(0));
+    }
+
+    #[test]
+    fn both_sneks_can_always_die_head_on() {
+        let mut state = versus(
+            vec![
+                Snek::line_snek(Position::new(1, 5), Direction::Right, 3),
+                Snek::baby_snek(Position::new(5, 5), Direction::Left),
+            ],
+            HeadOnRule::BothDie,
+        );
+        state.tick_all();
+        assert!(state.get_players().iter().all(|p| !p.is_alive()));
+        assert_eq!(state.get_winner(), None);
+    }
+
+    #[test]
+    fn sneks_cant_slither_through_each_other() {
+        let mut state = versus(
+            vec![
+                Snek::baby_snek(Position::new(4, 5), Direction::Right),
+                Snek::baby_snek(Position::new(5, 5), Direction::Left),
+            ],
+            HeadOnRule::LongerWins,
+        );
+        assert_eq!(
+            state.tick_all(),
+            vec![
+                SlitherResult::Died(DeathCause::HeadOn),
+                SlitherResult::Died(DeathCause::HeadOn)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_snek_dies_running_into_another_snek() {
+        let mut state = versus(
+            vec![
+                Snek::baby_snek(Position::new(2, 3), Direction::Down),
+                Snek::line_snek(Position::new(1, 4), Direction::Right, 3),
+            ],
+            HeadOnRule::LongerWins,
+        );
+        let slither_results = state.tick_all();
+        assert_eq!(
+            slither_results[0],
+            SlitherResult::Died(DeathCause::OtherSnek)
+        );
+        assert!(matches!(
+            slither_results[1],
+            SlitherResult::Slithered { .. }
+        ));
+        assert!(state.is_game_over());
+        assert_eq!(state.get_winner(), Some(1));
+        assert_eq!(
+            state.get_players()[0].get_death_cause(),
+            Some(DeathCause::OtherSnek)
+        );
+    }
+
+    #[test]
+    fn a_snek_can_follow_another_sneks_tail() {
+        let mut state = versus(
+            vec![
+                Snek::baby_snek(Position::new(1, 3), Direction::Down),
+                Snek::line_snek(Position::new(1, 4), Direction::Right, 3),
+            ],
+            HeadOnRule::LongerWins,
+        );
+        assert!(state
+            .tick_all()
+            .iter()
+            .all(|r| matches!(r, SlitherResult::Slithered { .. })));
+        assert!(!state.is_game_over());
+    }
+
+    #[test]
+    fn the_game_goes_on_until_one_snek_is_left() {
+        let mut state = State::with_options(12, 12, 5, GameOptions::two_player());
+        // player one heads up into the wall while player two keeps going
+        state.turn_player(0, Direction::Up);
+        let slither_results = state.tick_all();
+        assert_eq!(slither_results[0], SlitherResult::Died(DeathCause::Wall));
+        assert!(state.is_game_over());
+        assert_eq!(state.get_winner(), Some(1));
+        // sneks that are already dead keep reporting how they died
+        assert_eq!(state.tick_all()[0], SlitherResult::Died(DeathCause::Wall));
+    }
+
+    #[test]
+    fn dead_sneks_cant_turn() {
+        let mut state = State::with_seed(10, 10, 1);
+        state.turn_snek(Direction::Up);
+        state.tick();
+        assert!(!state.turn_snek(Direction::Right));
+    }
+
+    #[test]
+    fn players_can_join_partway_through() {
+        let mut state = State::with_seed(12, 12, 8);
+        state.start_recording();
+        state.tick();
+        assert_eq!(state.add_player(), Some(1));
+        let head = state.get_players()[1].get_snek().get_head();
+        let ahead = state
+            .get_walls()
+            .neighbor(&head.get_position(), head.get_direction());
+        assert!(ahead.is_some_and(|p| !state.is_wall(&p)));
+        assert_eq!(state.get_options().players, 2);
+        // joins can't be replayed
+        assert!(state.get_replay().is_none());
+        state.kill_player(1, DeathCause::Disconnected);
+        assert!(state.is_game_over());
+        assert_eq!(state.get_winner(), Some(0));
+    }
+
+    #[test]
+    fn nobody_can_join_a_full_board() {
+        let mut state = State::with_seed(4, 4, 8);
+        assert_eq!(state.add_player(), None);
+    }
+
+    #[test]
+    fn restoring_a_snapshot_turns_back_time() {
+        let mut state = State::with_options(12, 12, 14, GameOptions::orchard());
+        state.tick();
+        let snapshot = state.snapshot();
+        let hash = state.get_hash();
+        assert_eq!(snapshot.get_hash(), hash);
+        let mut expected = Vec::new();
+        for _ in 0..5 {
+            expected.push(state.tick());
+        }
+        assert_ne!(state.get_hash(), hash);
+        state.restore(&snapshot);
+        assert_eq!(state.get_hash(), hash);
+        assert_eq!(state.get_tick(), 1);
+        let mut recounted = state.clone();
+        recounted.recount();
+        assert_eq!(state.occupancy, recounted.occupancy);
+        for expected in expected {
+            assert_eq!(state.tick(), expected);
+        }
+    }
+
+    #[test]
+    fn games_that_play_out_differently_hash_differently() {
+        let mut one = State::with_seed(12, 12, 3);
+        let mut two = State::with_seed(12, 12, 3);
+        assert_eq!(one.get_hash(), two.get_hash());
+        one.turn_snek(Direction::Down);
+        one.tick();
+        two.tick();
+        assert_ne!(one.get_hash(), two.get_hash());
+    }
+
+    #[test]
+    fn it_loads_games_saved_before_there_were_players() {
+        let mut state = State::with_seed(10, 10, 6);
+        state.tick();
+        let player = serde_json::to_string(&state.get_players()[0]).unwrap();
+        let saved_game = serde_json::to_string(&state).unwrap().replace(
+            &format!("\"players\":[{}]", player),
+            &player[1..player.len() - 1],
+        );
+        assert!(!saved_game.contains("\"players\":["));
+        let loaded: State = serde_json::from_str(&saved_game).unwrap();
+        assert_eq!(loaded, state);
+    }
+
+    fn direction_state(direction: Direction) -> State {
+        let mut state = State::new(10, 10);
+        state.players[0].set_direction(direction);
+        state.players[0]
+            .get_snek_mut()
+            .get_head_mut()
+            .set_direction(&direction);
+        state
+    }
+
+    #[test]
+    fn snek_can_turn_from_up_to_left() {
+        let mut state = direction_state(Direction::Up);
+        assert_eq!(state.get_direction(), Direction::Up);
+        assert!(state.turn_snek(Direction::Left));
+        state.tick();
+        assert_eq!(state.get_direction(), Direction::Left);
+    }
+
+    #[test]
+    fn snek_can_turn_from_up_to_right() {
         let mut state = direction_state(Direction::Up);
         assert_eq!(state.get_direction(), Direction::Up);
         assert!(state.turn_snek(Direction::Right));
@@ -537,4 +2271,131 @@ mod tests {
         state.tick();
         assert_eq!(state.get_direction(), Direction::Left);
     }
+
+    #[test]
+    fn advancing_plays_the_same_game_as_ticking() {
+        let mut ticked = State::with_options(10, 10, 5, GameOptions::orchard());
+        let mut advanced = ticked.clone();
+        let mut autopilot = Autopilot::new();
+        while !ticked.is_game_over() && ticked.get_tick() < 300 {
+            if let Some(direction) = autopilot.steer(&ticked, 0) {
+                ticked.turn_snek(direction);
+                advanced.turn_snek(direction);
+            }
+            ticked.tick_all();
+            advanced.advance();
+            assert_eq!(ticked.get_hash(), advanced.get_hash());
+        }
+        assert!(ticked.get_score() > 0);
+    }
+
+    #[test]
+    fn the_board_keeps_track_of_whats_where() {
+        let options = GameOptions {
+            apple_varieties: true,
+            power_ups: true,
+            ..GameOptions::two_player()
+        };
+        let mut state = State::with_options(12, 12, 9, options);
+        let mut autopilots = [Autopilot::new(), Autopilot::new()];
+        while !state.is_game_over() && state.get_tick() < 500 {
+            for (player, autopilot) in autopilots.iter_mut().enumerate() {
+                if let Some(direction) = autopilot.steer(&state, player) {
+                    state.turn_player(player, direction);
+                }
+            }
+            state.tick_all();
+            let mut recounted = state.clone();
+            recounted.recount();
+            assert_eq!(state.occupancy, recounted.occupancy);
+        }
+        assert!(state.get_tick() > 20);
+    }
+
+    #[test]
+    fn it_picks_the_same_empty_cells_as_before() {
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 200);
+        let mut state = State::from_parts(
+            Walls::new(300, 100),
+            vec![snek],
+            Vec::new(),
+            4,
+            GameOptions::default(),
+        );
+        for _ in 0..50 {
+            let empty_positions = state.occupancy.get_empty_positions();
+            let mut rng = state.rng.clone();
+            let expected = empty_positions.choose(&mut rng).copied();
+            assert_eq!(state.choose_empty_position(), expected);
+            assert_eq!(state.rng, rng);
+            state.new_apple();
+        }
+        assert!(!state
+            .get_apples()
+            .iter()
+            .any(|a| state.is_snek_at(&a.get_position())));
+    }
+
+    #[test]
+    fn subscribers_hear_about_apples_and_points() {
+        let mut state = state_with_apple(1, Apple::new(Position::new(2, 1)));
+        let events = state.subscribe();
+        state.tick();
+        let events: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(events[0], GameEvent::TickStarted { tick: 0 });
+        assert!(events.contains(&GameEvent::AppleEaten {
+            player: 0,
+            apple: Apple::new(Position::new(2, 1)),
+        }));
+        assert!(events.contains(&GameEvent::ScoreChanged {
+            player: 0,
+            score: 1
+        }));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GameEvent::AppleSpawned { .. })));
+    }
+
+    #[test]
+    fn subscribers_hear_about_turns_and_deaths() {
+        let mut state = State::new(4, 4);
+        let events = state.subscribe();
+        assert!(state.turn_snek(Direction::Up));
+        assert!(!state.turn_snek(Direction::Down));
+        state.tick();
+        let events: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::Turned {
+                    player: 0,
+                    direction: Direction::Up
+                },
+                GameEvent::TurnRejected {
+                    player: 0,
+                    direction: Direction::Down
+                },
+                GameEvent::TickStarted { tick: 0 },
+                GameEvent::SnekDied {
+                    player: 0,
+                    cause: DeathCause::Wall
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn copies_of_a_game_start_with_nobody_listening() {
+        let mut state = State::new(10, 10);
+        let events = state.subscribe();
+        let mut copy = state.clone();
+        let mut fork = state.fork();
+        copy.tick();
+        fork.tick();
+        assert!(events.try_recv().is_err());
+        assert_eq!(copy, fork);
+        // nobody listening anymore is fine too
+        drop(events);
+        state.tick();
+    }
 }
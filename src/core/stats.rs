@@ -0,0 +1,311 @@
+use crate::core::{
+    data_dir,
+    high_scores::{describe_date, now},
+    DeathCause, Direction, GameEvent, State,
+};
+
+use crossbeam_channel::Receiver;
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// how many apples in a row a snek has to eat without turning to be a straight shooter
+pub const STRAIGHT_APPLES: usize = 10;
+/// how many apples have to be eaten, all told, for a hundred apples
+pub const LIFETIME_APPLES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstApple,
+    StraightShooter,
+    HalfTheBoard,
+    AteTheWorld,
+    HundredApples,
+    Resized,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 6] = [
+        Achievement::FirstApple,
+        Achievement::StraightShooter,
+        Achievement::HalfTheBoard,
+        Achievement::AteTheWorld,
+        Achievement::HundredApples,
+        Achievement::Resized,
+    ];
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            Achievement::FirstApple => "firssst bite",
+            Achievement::StraightShooter => "ssstraight ssshooter",
+            Achievement::HalfTheBoard => "half full",
+            Achievement::AteTheWorld => "world eater",
+            Achievement::HundredApples => "a hundred applesss",
+            Achievement::Resized => "ssshrunk",
+        }
+    }
+
+    /// what it takes to unlock
+    pub fn describe(&self) -> &str {
+        match self {
+            Achievement::FirstApple => "eat an apple",
+            Achievement::StraightShooter => "eat 10 apples without turning",
+            Achievement::HalfTheBoard => "fill half the board",
+            Achievement::AteTheWorld => "eat the world",
+            Achievement::HundredApples => "eat 100 apples, all told",
+            Achievement::Resized => "die to a resize",
+        }
+    }
+}
+
+/// everything that's happened across every game played here
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// games that ended, however they ended
+    pub games_played: usize,
+    pub apples: usize,
+    /// ticks the snek was alive for
+    pub ticks: usize,
+    pub longest_snek: usize,
+    pub deaths: BTreeMap<DeathCause, usize>,
+    pub worlds_eaten: usize,
+    /// when each achievement was unlocked, in seconds since the unix epoch
+    pub achievements: BTreeMap<Achievement, u64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// where the stats live in the data directory
+    pub fn default_path() -> io::Result<PathBuf> {
+        Ok(data_dir()?.join("stats.json"))
+    }
+
+    /// loads the stats, starting from nothing if the file doesn't exist yet
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match File::open(path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).map_err(io::Error::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self).map_err(io::Error::from)
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.achievements.contains_key(&achievement)
+    }
+
+    /// the date an achievement was unlocked on, as `YYYY-MM-DD`
+    pub fn describe_unlocked(&self, achievement: Achievement) -> Option<String> {
+        self.achievements
+            .get(&achievement)
+            .map(|d| describe_date(*d))
+    }
+
+    /// unlocks an achievement, returning whether it's the first time
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(achievement) {
+            return false;
+        }
+        self.achievements.insert(achievement, now());
+        true
+    }
+}
+
+/// follows one player through a game as it's played, counting
+/// it towards the [`Stats`] and unlocking achievements as they're earned
+#[derive(Debug)]
+pub struct StatsTracker {
+    events: Receiver<GameEvent>,
+    player: usize,
+    /// which way the snek was going at the last update
+    heading: Direction,
+    /// apples eaten since the snek last turned
+    straight_apples: usize,
+    /// whether the game's over for the player
+    finished: bool,
+}
+
+impl StatsTracker {
+    /// tracks `player` in `state`, hearing about the game from `events`
+    pub fn new(events: Receiver<GameEvent>, state: &State, player: usize) -> Self {
+        Self {
+            events,
+            player,
+            heading: state.get_players()[player].get_direction(),
+            straight_apples: 0,
+            finished: false,
+        }
+    }
+
+    /// counts everything that's happened since the last update towards
+    /// `stats`, returning any achievements that were unlocked for the first
+    /// time. call it after every tick, so every turn the snek makes is seen.
+    pub fn update(&mut self, stats: &mut Stats, state: &State) -> Vec<Achievement> {
+        let mut unlocked = Vec::new();
+        let mut unlock = |stats: &mut Stats, achievement| {
+            if stats.unlock(achievement) {
+                unlocked.push(achievement);
+            }
+        };
+        let snek = state.get_players()[self.player].get_snek();
+        let heading = snek.get_head().get_direction();
+        if heading != self.heading {
+            self.heading = heading;
+            self.straight_apples = 0;
+        }
+        for event in self.events.try_iter() {
+            match event {
+                GameEvent::TickStarted { .. } if !self.finished => stats.ticks += 1,
+                GameEvent::AppleEaten { player, .. } if player == self.player => {
+                    stats.apples += 1;
+                    self.straight_apples += 1;
+                    unlock(stats, Achievement::FirstApple);
+                    if self.straight_apples >= STRAIGHT_APPLES {
+                        unlock(stats, Achievement::StraightShooter);
+                    }
+                    if stats.apples >= LIFETIME_APPLES {
+                        unlock(stats, Achievement::HundredApples);
+                    }
+                }
+                GameEvent::SnekDied { player, cause } if player == self.player => {
+                    *stats.deaths.entry(cause).or_default() += 1;
+                    stats.games_played += 1;
+                    self.finished = true;
+                    if cause == DeathCause::Resized {
+                        unlock(stats, Achievement::Resized);
+                    }
+                }
+                GameEvent::AteTheWorld { player } if player == self.player => {
+                    stats.worlds_eaten += 1;
+                    stats.games_played += 1;
+                    self.finished = true;
+                    unlock(stats, Achievement::AteTheWorld);
+                }
+                _ => {}
+            }
+        }
+        if !self.finished && state.is_game_over() {
+            // the player won, or the game ended some other way with their snek still going
+            stats.games_played += 1;
+            self.finished = true;
+        }
+        let length = snek.count_segments();
+        stats.longest_snek = stats.longest_snek.max(length);
+        if length * 2 >= state.get_walls().get_max_segments() {
+            unlock(stats, Achievement::HalfTheBoard);
+        }
+        unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Apple, GameOptions, Position, Snek, Walls};
+
+    /// a snek heading right along a row of apples
+    fn apple_row(apples: usize) -> State {
+        let snek = Snek::line_snek(Position::new(1, 1), Direction::Right, 1);
+        let apples = (0..apples)
+            .map(|x| Apple::new(Position::new(x + 2, 1)))
+            .collect();
+        State::from_parts(
+            Walls::new(30, 30),
+            vec![snek],
+            apples,
+            0,
+            GameOptions::default(),
+        )
+    }
+
+    #[test]
+    fn it_counts_a_game_as_its_played() {
+        let mut state = apple_row(3);
+        let mut stats = Stats::new();
+        let mut tracker = StatsTracker::new(state.subscribe(), &state, 0);
+        for _ in 0..3 {
+            state.tick();
+            tracker.update(&mut stats, &state);
+        }
+        state.kill_player(0, DeathCause::Resized);
+        assert_eq!(
+            tracker.update(&mut stats, &state),
+            vec![Achievement::Resized]
+        );
+        assert_eq!(stats.apples, 3);
+        assert_eq!(stats.ticks, 3);
+        assert_eq!(stats.longest_snek, 4);
+        assert_eq!(stats.games_played, 1);
+        assert_eq!(stats.deaths[&DeathCause::Resized], 1);
+        assert!(stats.is_unlocked(Achievement::FirstApple));
+        assert!(!stats.is_unlocked(Achievement::StraightShooter));
+    }
+
+    #[test]
+    fn eating_apples_without_turning_makes_a_straight_shooter() {
+        let mut state = apple_row(STRAIGHT_APPLES);
+        let mut stats = Stats::new();
+        let mut tracker = StatsTracker::new(state.subscribe(), &state, 0);
+        let mut unlocked = Vec::new();
+        for _ in 0..STRAIGHT_APPLES {
+            state.tick();
+            unlocked.extend(tracker.update(&mut stats, &state));
+        }
+        assert_eq!(
+            unlocked,
+            vec![Achievement::FirstApple, Achievement::StraightShooter]
+        );
+        // a turn starts the count again
+        let mut state = apple_row(STRAIGHT_APPLES);
+        let mut tracker = StatsTracker::new(state.subscribe(), &state, 0);
+        let mut stats = Stats::new();
+        state.tick();
+        tracker.update(&mut stats, &state);
+        state.turn_snek(Direction::Down);
+        state.tick();
+        tracker.update(&mut stats, &state);
+        assert_eq!(tracker.straight_apples, 0);
+    }
+
+    #[test]
+    fn achievements_only_unlock_once() {
+        let mut stats = Stats::new();
+        assert!(stats.unlock(Achievement::HalfTheBoard));
+        assert!(!stats.unlock(Achievement::HalfTheBoard));
+        assert!(stats.describe_unlocked(Achievement::HalfTheBoard).is_some());
+        assert_eq!(stats.describe_unlocked(Achievement::AteTheWorld), None);
+    }
+
+    #[test]
+    fn it_loads_deaths_saved_under_the_old_names() {
+        let stats: Stats = serde_json::from_str(r#"{"deaths":{"Tail":3,"HeadOn":1}}"#).unwrap();
+        assert_eq!(stats.deaths[&DeathCause::Tail], 3);
+        assert_eq!(stats.deaths[&DeathCause::HeadOn], 1);
+    }
+
+    #[test]
+    fn it_round_trips_through_a_file() {
+        let mut stats = Stats::new();
+        stats.games_played = 4;
+        stats.deaths.insert(DeathCause::Tail, 3);
+        stats.unlock(Achievement::FirstApple);
+        let path = std::env::temp_dir().join(format!("snek-stats-{}.json", std::process::id()));
+        stats.save(&path).unwrap();
+        let loaded = Stats::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, stats);
+    }
+}
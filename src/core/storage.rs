@@ -0,0 +1,23 @@
+use std::{env, fs, io, path::PathBuf};
+
+/// the directory snek keeps its files in, following the
+/// XDG base directory spec: `$XDG_DATA_HOME/snek_rs`,
+/// falling back to `~/.local/share/snek_rs`.
+/// the directory is created if it doesn't exist yet.
+pub fn data_dir() -> io::Result<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(data_home) => PathBuf::from(data_home),
+        None => {
+            let home = env::var_os("HOME").ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "neither $XDG_DATA_HOME nor $HOME are set",
+                )
+            })?;
+            PathBuf::from(home).join(".local").join("share")
+        }
+    };
+    let data_dir = base.join("snek_rs");
+    fs::create_dir_all(&data_dir)?;
+    Ok(data_dir)
+}
@@ -0,0 +1,77 @@
+use crate::core::{Direction, Position};
+
+use serde::{Deserialize, Serialize};
+
+/// how the edges of the board connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Topology {
+    /// the board is surrounded by walls
+    #[default]
+    Bounded,
+    /// leaving one edge of the board re-enters on the opposite edge
+    Toroidal,
+}
+
+impl Topology {
+    /// the position one step away in `direction` on a board of `width` x `height`,
+    /// or `None` if there's nothing there
+    pub fn neighbor(
+        &self,
+        position: &Position,
+        direction: Direction,
+        width: usize,
+        height: usize,
+    ) -> Option<Position> {
+        match self {
+            Topology::Bounded => position.neighbor(direction),
+            Topology::Toroidal => Some(position.wrapping_neighbor(direction, width, height)),
+        }
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            Topology::Bounded => "bounded",
+            Topology::Toroidal => "wraparound",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_boards_have_edges() {
+        let topology = Topology::Bounded;
+        let corner = Position::new(0, 0);
+        assert_eq!(topology.neighbor(&corner, Direction::Up, 5, 5), None);
+        assert_eq!(topology.neighbor(&corner, Direction::Left, 5, 5), None);
+        assert_eq!(
+            topology.neighbor(&corner, Direction::Right, 5, 5),
+            Some(Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn toroidal_boards_wrap_around() {
+        let topology = Topology::Toroidal;
+        let corner = Position::new(0, 0);
+        assert_eq!(
+            topology.neighbor(&corner, Direction::Up, 5, 4),
+            Some(Position::new(0, 3))
+        );
+        assert_eq!(
+            topology.neighbor(&corner, Direction::Left, 5, 4),
+            Some(Position::new(4, 0))
+        );
+        let opposite_corner = Position::new(4, 3);
+        assert_eq!(
+            topology.neighbor(&opposite_corner, Direction::Down, 5, 4),
+            Some(Position::new(4, 0))
+        );
+        assert_eq!(
+            topology.neighbor(&opposite_corner, Direction::Right, 5, 4),
+            Some(Position::new(0, 3))
+        );
+    }
+}
@@ -1,64 +1,165 @@
-use crate::core::Position;
+use crate::core::{Direction, Position, Topology};
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+/// the cells of the board nothing can move through.
+/// a classic board is just a rectangular border, but any
+/// cell can be blocked to make pillars, corridors and rooms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "WallsFile")]
 pub struct Walls {
     width: usize,
     height: usize,
+    topology: Topology,
+    blocked: Vec<bool>,
+}
+
+/// games saved before walls could be arbitrary shapes
+/// only stored the board's size, so they get a border
+#[derive(Deserialize)]
+struct WallsFile {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    topology: Topology,
+    blocked: Option<Vec<bool>>,
+}
+
+impl TryFrom<WallsFile> for Walls {
+    type Error = String;
+
+    /// a board whose cells don't add up to its size would
+    /// panic the first time anything looked at it
+    fn try_from(walls_file: WallsFile) -> Result<Self, Self::Error> {
+        let (width, height) = (walls_file.width, walls_file.height);
+        match walls_file.blocked {
+            Some(blocked) if Some(blocked.len()) != width.checked_mul(height) => Err(format!(
+                "a {}x{} board can't have {} cells",
+                width,
+                height,
+                blocked.len()
+            )),
+            Some(blocked) => Ok(Self {
+                width,
+                height,
+                topology: walls_file.topology,
+                blocked,
+            }),
+            None if width == 0 || height == 0 => {
+                Err(format!("a {}x{} board has no cells", width, height))
+            }
+            None => Ok(Self::with_topology(width, height, walls_file.topology)),
+        }
+    }
 }
 
 impl Walls {
     pub fn new(width: usize, height: usize) -> Self {
-        Self { width, height }
+        Self::with_topology(width, height, Topology::Bounded)
     }
-    pub fn collides_with(&self, position: &Position) -> bool {
-        !self.is_position_inside(position)
+
+    /// a bounded board is surrounded by a border,
+    /// a toroidal board has no walls at all since
+    /// its edges wrap around instead
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
+        let mut walls = Self::empty(width, height, topology);
+        if let Topology::Bounded = topology {
+            for x in 0..width {
+                walls.add_wall(Position::new(x, 0));
+                walls.add_wall(Position::new(x, height - 1));
+            }
+            for y in 0..height {
+                walls.add_wall(Position::new(0, y));
+                walls.add_wall(Position::new(width - 1, y));
+            }
+        }
+        walls
     }
 
-    pub fn is_position_inside(&self, position: &Position) -> bool {
-        let (x, y) = position.get_coordinates();
-        self.is_x_inside(x) && self.is_y_inside(y)
+    /// a board without a single wall on it
+    pub fn empty(width: usize, height: usize, topology: Topology) -> Self {
+        Self {
+            width,
+            height,
+            topology,
+            blocked: vec![false; width * height],
+        }
     }
 
-    pub(crate) fn get_max_segments(&self) -> usize {
-        (self.width - 2) * (self.height - 2)
+    pub fn get_topology(&self) -> Topology {
+        self.topology
     }
 
-    fn is_x_inside(&self, x: usize) -> bool {
-        x > self.left_wall() && x < self.right_wall()
+    /// the position one step away from `position` in `direction`,
+    /// following the board's topology
+    pub fn neighbor(&self, position: &Position, direction: Direction) -> Option<Position> {
+        self.topology
+            .neighbor(position, direction, self.width, self.height)
     }
 
-    fn is_y_inside(&self, y: usize) -> bool {
-        y > self.top_wall() && y < self.bottom_wall()
+    pub fn get_dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
     }
 
-    pub(crate) fn top_wall(&self) -> usize {
-        0
+    /// blocks off a cell. positions off the board are ignored.
+    pub fn add_wall(&mut self, position: Position) {
+        if let Some(i) = self.get_index(&position) {
+            self.blocked[i] = true;
+        }
     }
 
-    pub(crate) fn left_wall(&self) -> usize {
-        0
+    pub fn remove_wall(&mut self, position: Position) {
+        if let Some(i) = self.get_index(&position) {
+            self.blocked[i] = false;
+        }
     }
 
-    pub(crate) fn bottom_wall(&self) -> usize {
-        self.height - 1
+    /// whether a position is a wall. anything
+    /// off the edge of the board counts as one.
+    pub fn is_wall(&self, position: &Position) -> bool {
+        self.get_index(position).is_none_or(|i| self.blocked[i])
     }
 
-    pub(crate) fn right_wall(&self) -> usize {
-        self.width - 1
+    pub fn collides_with(&self, position: &Position) -> bool {
+        !self.is_position_inside(position)
     }
 
-    pub fn get_positions(&self) -> Vec<Position> {
-        let mut positions = Vec::new();
-        for x in self.left_wall()..=self.right_wall() {
-            positions.push(Position::new(x, self.bottom_wall()));
-            positions.push(Position::new(x, self.top_wall()));
-        }
-        for y in self.top_wall()..=self.bottom_wall() {
-            positions.push(Position::new(self.left_wall(), y));
-            positions.push(Position::new(self.right_wall(), y));
+    pub fn is_position_inside(&self, position: &Position) -> bool {
+        !self.is_wall(position)
+    }
+
+    /// how long a snek could get if it filled every free cell
+    pub(crate) fn get_max_segments(&self) -> usize {
+        self.blocked.iter().filter(|blocked| !**blocked).count()
+    }
+
+    fn get_index(&self, position: &Position) -> Option<usize> {
+        let (x, y) = position.get_coordinates();
+        if x < self.width && y < self.height {
+            Some(x + y * self.width)
+        } else {
+            None
         }
-        tracing::info!("{:?}", &positions);
-        positions
+    }
+
+    fn get_position(&self, i: usize) -> Position {
+        Position::new(i % self.width, i / self.width)
+    }
+
+    /// every wall on the board
+    pub fn get_positions(&self) -> Vec<Position> {
+        (0..self.blocked.len())
+            .filter(|i| self.blocked[*i])
+            .map(|i| self.get_position(i))
+            .collect()
+    }
+
+    /// every cell on the board that isn't a wall
+    pub fn get_free_positions(&self) -> Vec<Position> {
+        (0..self.blocked.len())
+            .filter(|i| !self.blocked[*i])
+            .map(|i| self.get_position(i))
+            .collect()
     }
 }
 
@@ -131,6 +232,91 @@ mod tests {
         assert!(walls.collides_with(&bottom_right_corner));
     }
 
+    #[test]
+    fn toroidal_walls_have_no_walls() {
+        let walls = Walls::with_topology(5, 5, Topology::Toroidal);
+        assert!(walls.get_positions().is_empty());
+        assert_eq!(walls.get_max_segments(), 25);
+        for x in 0..5 {
+            for y in 0..5 {
+                assert!(!walls.collides_with(&Position::new(x, y)));
+            }
+        }
+        assert!(walls.collides_with(&Position::new(5, 0)));
+    }
+
+    #[test]
+    fn walls_know_neighbors_by_topology() {
+        let bounded = Walls::new(5, 5);
+        let toroidal = Walls::with_topology(5, 5, Topology::Toroidal);
+        let corner = Position::new(0, 0);
+        assert_eq!(bounded.neighbor(&corner, Direction::Left), None);
+        assert_eq!(
+            toroidal.neighbor(&corner, Direction::Left),
+            Some(Position::new(4, 0))
+        );
+    }
+
+    #[test]
+    fn walls_can_be_any_shape() {
+        // [X,X,X,X,X]
+        // [X,o,o,o,X]
+        // [X,o,X,o,X]
+        // [X,o,o,o,X]
+        // [X,X,X,X,X]
+        let mut walls = Walls::new(5, 5);
+        let pillar = Position::new(2, 2);
+        assert!(walls.is_position_inside(&pillar));
+        walls.add_wall(pillar);
+        assert!(walls.collides_with(&pillar));
+        assert!(walls.is_wall(&pillar));
+        assert_eq!(walls.get_max_segments(), 8);
+        assert!(walls.get_positions().contains(&pillar));
+        assert!(!walls.get_free_positions().contains(&pillar));
+        assert_eq!(walls.get_free_positions().len(), 8);
+        walls.remove_wall(pillar);
+        assert!(walls.is_position_inside(&pillar));
+        assert_eq!(walls.get_max_segments(), 9);
+    }
+
+    #[test]
+    fn empty_walls_have_no_walls_on_the_board() {
+        let mut walls = Walls::empty(4, 3, Topology::Bounded);
+        assert_eq!(walls.get_max_segments(), 12);
+        assert!(walls.get_positions().is_empty());
+        // but the edge of the board is still a wall
+        assert!(walls.collides_with(&Position::new(4, 0)));
+        assert_eq!(walls.neighbor(&Position::new(0, 0), Direction::Up), None);
+        // walls off the board are ignored
+        walls.add_wall(Position::new(10, 10));
+        assert!(walls.get_positions().is_empty());
+    }
+
+    #[test]
+    fn walls_load_from_old_saves() {
+        let walls: Walls = serde_json::from_str(r#"{"width":5,"height":4}"#).unwrap();
+        assert_eq!(walls, Walls::new(5, 4));
+        let toroidal: Walls =
+            serde_json::from_str(r#"{"width":5,"height":4,"topology":"Toroidal"}"#).unwrap();
+        assert_eq!(toroidal, Walls::with_topology(5, 4, Topology::Toroidal));
+    }
+
+    #[test]
+    fn walls_that_dont_fit_the_board_dont_load() {
+        let json = r#"{"width":3,"height":3,"blocked":[true,false,true]}"#;
+        assert!(serde_json::from_str::<Walls>(json).is_err());
+        let json = r#"{"width":0,"height":7}"#;
+        assert!(serde_json::from_str::<Walls>(json).is_err());
+    }
+
+    #[test]
+    fn walls_round_trip_through_serde() {
+        let mut walls = Walls::new(6, 6);
+        walls.add_wall(Position::new(3, 3));
+        let json = serde_json::to_string(&walls).unwrap();
+        assert_eq!(serde_json::from_str::<Walls>(&json).unwrap(), walls);
+    }
+
     #[test]
     fn walls_are_sized_correctly_when_wide() {
         // [X,X,X,X,X,X]
@@ -0,0 +1,208 @@
+use crate::gym::{Action, Env, EnvOptions, StepInfo};
+
+use rayon::prelude::*;
+
+/// thousands of [`Env`]s stepped all at once across every core. what they
+/// all see is written into one buffer, one observation after another, so
+/// it can be handed straight to a training library without copying.
+/// an episode that finishes is started over on the same step, so every
+/// env always has a game going.
+#[derive(Debug, Clone)]
+pub struct BatchEnv {
+    options: EnvOptions,
+    envs: Vec<Env>,
+    /// how many episodes each env has played, so every episode gets its own seed
+    episodes: Vec<u64>,
+    seed: u64,
+    observations: Vec<f32>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    infos: Vec<StepInfo>,
+}
+
+impl BatchEnv {
+    /// `count` envs that all play by `options`, starting with the games for `seed`
+    pub fn new(options: EnvOptions, count: usize, seed: u64) -> Self {
+        let mut batch = Self {
+            options,
+            envs: vec![Env::new(options); count],
+            episodes: vec![0; count],
+            seed,
+            observations: vec![0.0; count * options.observation_len()],
+            rewards: vec![0.0; count],
+            dones: vec![false; count],
+            infos: vec![StepInfo::default(); count],
+        };
+        batch.reset(seed);
+        batch
+    }
+
+    /// starts every env over. env `i`'s `n`th episode
+    /// after this is seeded with `seed + i + n * count`.
+    pub fn reset(&mut self, seed: u64) -> &[f32] {
+        self.seed = seed;
+        let len = self.observation_len();
+        self.envs
+            .par_iter_mut()
+            .zip(self.observations.par_chunks_mut(len))
+            .enumerate()
+            .for_each(|(i, (env, observation))| {
+                env.reset_into(seed.wrapping_add(i as u64), observation);
+            });
+        self.episodes.fill(1);
+        self.rewards.fill(0.0);
+        self.dones.fill(false);
+        self.infos.fill(StepInfo::default());
+        &self.observations
+    }
+
+    /// steps env `i` with `actions[i]`, for every env at once. envs whose
+    /// episode finished are started over, so their observation is the start
+    /// of the next episode, while their reward, done and info are still
+    /// about how the last one ended.
+    pub fn step(&mut self, actions: &[Action]) {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "there needs to be an action for every env"
+        );
+        let len = self.observation_len();
+        let (seed, count) = (self.seed, self.envs.len() as u64);
+        self.envs
+            .par_iter_mut()
+            .zip(self.episodes.par_iter_mut())
+            .zip(self.observations.par_chunks_mut(len))
+            .zip(self.rewards.par_iter_mut())
+            .zip(self.dones.par_iter_mut())
+            .zip(self.infos.par_iter_mut())
+            .zip(actions.par_iter())
+            .enumerate()
+            .with_min_len(64)
+            .for_each(
+                |(i, ((((((env, episodes), observation), reward), done), info), action))| {
+                    (*reward, *done, *info) = env.step_into(*action, observation);
+                    if *done {
+                        let episode_seed = seed
+                            .wrapping_add(i as u64)
+                            .wrapping_add(episodes.wrapping_mul(count));
+                        env.reset_into(episode_seed, observation);
+                        *episodes += 1;
+                    }
+                },
+            );
+    }
+
+    /// every env's observation, one after another
+    pub fn get_observations(&self) -> &[f32] {
+        &self.observations
+    }
+
+    /// env `i`'s observation
+    pub fn get_observation(&self, i: usize) -> &[f32] {
+        let len = self.observation_len();
+        &self.observations[i * len..(i + 1) * len]
+    }
+
+    /// what each env was rewarded on the last step
+    pub fn get_rewards(&self) -> &[f32] {
+        &self.rewards
+    }
+
+    /// which envs finished an episode on the last step
+    pub fn get_dones(&self) -> &[bool] {
+        &self.dones
+    }
+
+    pub fn get_infos(&self) -> &[StepInfo] {
+        &self.infos
+    }
+
+    pub fn get_env(&self, i: usize) -> &Env {
+        &self.envs[i]
+    }
+
+    /// how many numbers each env's observation has
+    pub fn observation_len(&self) -> usize {
+        self.options.observation_len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gym::ObservationKind;
+
+    fn options() -> EnvOptions {
+        EnvOptions {
+            width: 8,
+            height: 8,
+            observation: ObservationKind::Egocentric,
+            ..EnvOptions::default()
+        }
+    }
+
+    #[test]
+    fn it_plays_the_same_games_as_one_env_at_a_time() {
+        let mut batch = BatchEnv::new(options(), 5, 10);
+        let mut envs: Vec<Env> = (0..5).map(|_| Env::new(options())).collect();
+        for (i, env) in envs.iter_mut().enumerate() {
+            assert_eq!(env.reset(10 + i as u64), batch.get_observation(i));
+        }
+        let actions = [
+            Action::Straight,
+            Action::Left,
+            Action::Right,
+            Action::Left,
+            Action::Straight,
+        ];
+        for _ in 0..3 {
+            batch.step(&actions);
+            for (i, env) in envs.iter_mut().enumerate() {
+                let (observation, reward, done, info) = env.step(actions[i]);
+                assert_eq!(batch.get_rewards()[i], reward);
+                assert_eq!(batch.get_dones()[i], done);
+                assert_eq!(batch.get_infos()[i], info);
+                if !done {
+                    assert_eq!(batch.get_observation(i), observation);
+                }
+            }
+            if envs.iter().any(|env| env.is_done()) {
+                break;
+            }
+        }
+        assert_eq!(batch.get_observations().len(), 5 * batch.observation_len());
+    }
+
+    #[test]
+    fn finished_episodes_start_over() {
+        let mut batch = BatchEnv::new(options(), 3, 0);
+        let mut finished = 0;
+        for _ in 0..20 {
+            // going straight on runs into a wall sooner or later
+            batch.step(&[Action::Straight; 3]);
+            for i in 0..3 {
+                if batch.get_dones()[i] {
+                    finished += 1;
+                    assert!(batch.get_infos()[i].death_cause.is_some());
+                    assert_eq!(batch.get_env(i).get_state().get_tick(), 0);
+                    assert!(!batch.get_env(i).is_done());
+                }
+            }
+        }
+        assert!(finished >= 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "an action for every env")]
+    fn every_env_needs_an_action() {
+        BatchEnv::new(options(), 2, 0).step(&[Action::Straight]);
+    }
+}
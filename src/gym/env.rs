@@ -0,0 +1,285 @@
+use crate::core::{DeathCause, Direction, GameOptions, State};
+use crate::gym::{ObservationKind, Rewards};
+
+/// what an agent sees of the board, laid out the way its [`ObservationKind`] says
+pub type Observation = Vec<f32>;
+
+/// which way an agent can steer, relative to the way the snek is heading.
+/// there's no turning back on itself, so there are only three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Straight,
+    Left,
+    Right,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Straight, Action::Left, Action::Right];
+
+    /// the action numbered `index` in [`Action::ALL`], for agents that pick by number
+    pub fn from_index(index: usize) -> Option<Action> {
+        Action::ALL.get(index).copied()
+    }
+
+    /// which way a snek heading `heading` ends up going
+    pub fn apply(&self, heading: Direction) -> Direction {
+        match self {
+            Action::Straight => heading,
+            Action::Left => heading.left(),
+            Action::Right => heading.right(),
+        }
+    }
+}
+
+/// how an [`Env`]'s games are set up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvOptions {
+    pub width: usize,
+    pub height: usize,
+    /// the agent plays player one. any other sneks never turn.
+    pub game: GameOptions,
+    pub rewards: Rewards,
+    pub observation: ObservationKind,
+    /// how many ticks an agent can go without scoring before the episode is
+    /// cut short. it's twice as many as there are cells on the board if it isn't set.
+    pub patience: Option<usize>,
+}
+
+impl Default for EnvOptions {
+    fn default() -> Self {
+        Self {
+            width: 10,
+            height: 10,
+            game: GameOptions::default(),
+            rewards: Rewards::default(),
+            observation: ObservationKind::default(),
+            patience: None,
+        }
+    }
+}
+
+impl EnvOptions {
+    /// how many numbers each observation has
+    pub fn observation_len(&self) -> usize {
+        self.observation.len(self.width, self.height)
+    }
+
+    pub(crate) fn get_patience(&self) -> usize {
+        self.patience.unwrap_or(self.width * self.height * 2)
+    }
+}
+
+/// what happened on a step, beyond the reward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StepInfo {
+    pub score: usize,
+    pub tick: usize,
+    pub length: usize,
+    /// how the snek died, if it did
+    pub death_cause: Option<DeathCause>,
+    pub ate_the_world: bool,
+    /// whether the episode was cut short because the agent stopped scoring
+    pub truncated: bool,
+}
+
+/// a game an agent can learn to play one step at a time, the
+/// same way as the reinforcement learning libraries expect
+#[derive(Debug, Clone)]
+pub struct Env {
+    options: EnvOptions,
+    state: State,
+    scored_at: usize,
+    done: bool,
+}
+
+impl Env {
+    pub fn new(options: EnvOptions) -> Self {
+        Self {
+            state: State::with_options(options.width, options.height, 0, options.game),
+            options,
+            scored_at: 0,
+            done: false,
+        }
+    }
+
+    /// starts a new episode, returning what the agent sees at the start
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let mut observation = vec![0.0; self.observation_len()];
+        self.reset_into(seed, &mut observation);
+        observation
+    }
+
+    /// starts a new episode, writing what the agent sees at the start into `observation`
+    pub fn reset_into(&mut self, seed: u64, observation: &mut [f32]) {
+        self.state = State::with_options(
+            self.options.width,
+            self.options.height,
+            seed,
+            self.options.game,
+        );
+        self.scored_at = 0;
+        self.done = false;
+        self.observe_into(observation);
+    }
+
+    /// steers the snek and moves the game forward a tick. once it's done,
+    /// the episode has to be [`Env::reset`] before it can be stepped again.
+    pub fn step(&mut self, action: Action) -> (Observation, f32, bool, StepInfo) {
+        let mut observation = vec![0.0; self.observation_len()];
+        let (reward, done, info) = self.step_into(action, &mut observation);
+        (observation, reward, done, info)
+    }
+
+    /// [`Env::step`], but writing the observation into `observation`
+    /// instead of handing back a new one
+    pub fn step_into(&mut self, action: Action, observation: &mut [f32]) -> (f32, bool, StepInfo) {
+        assert!(!self.done, "stepped an episode that's already done");
+        let heading = self.state.get_snek().get_head().get_direction();
+        self.state.set_course(0, action.apply(heading));
+        let score = self.state.get_score();
+        self.state.advance();
+        let rewards = self.options.rewards;
+        let scored = self.state.get_score().saturating_sub(score);
+        if scored > 0 {
+            self.scored_at = self.state.get_tick();
+        }
+        let player = &self.state.get_players()[0];
+        let length = player.get_snek().count_segments();
+        let mut info = StepInfo {
+            score: player.get_score(),
+            tick: self.state.get_tick(),
+            length,
+            death_cause: player.get_death_cause(),
+            ate_the_world: length == self.state.get_walls().get_max_segments(),
+            truncated: false,
+        };
+        let mut reward = rewards.tick + rewards.apple * scored as f32;
+        if info.death_cause.is_some() {
+            reward += rewards.death;
+        } else if info.ate_the_world {
+            reward += rewards.win;
+        } else if self.state.get_tick() - self.scored_at > self.options.get_patience() {
+            info.truncated = true;
+        }
+        self.done = info.death_cause.is_some() || info.ate_the_world || info.truncated;
+        self.observe_into(observation);
+        (reward, self.done, info)
+    }
+
+    fn observe_into(&self, observation: &mut [f32]) {
+        self.options.observation.write(&self.state, 0, observation);
+    }
+
+    /// how many numbers each observation has
+    pub fn observation_len(&self) -> usize {
+        self.options.observation_len()
+    }
+
+    pub fn get_state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn get_options(&self) -> &EnvOptions {
+        &self.options
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Autopilot, Controller};
+
+    fn egocentric() -> EnvOptions {
+        EnvOptions {
+            observation: ObservationKind::Egocentric,
+            ..EnvOptions::default()
+        }
+    }
+
+    #[test]
+    fn actions_are_relative_to_the_heading() {
+        assert_eq!(Action::Left.apply(Direction::Up), Direction::Left);
+        assert_eq!(Action::Right.apply(Direction::Up), Direction::Right);
+        assert_eq!(Action::Left.apply(Direction::Right), Direction::Up);
+        assert_eq!(Action::Straight.apply(Direction::Down), Direction::Down);
+        assert_eq!(Action::from_index(2), Some(Action::Right));
+        assert_eq!(Action::from_index(3), None);
+    }
+
+    #[test]
+    fn running_into_a_wall_is_punished() {
+        let mut env = Env::new(egocentric());
+        let observation = env.reset(1);
+        assert_eq!(observation.len(), env.observation_len());
+        let mut total = 0.0;
+        let mut steps = 0;
+        loop {
+            let (_, reward, done, info) = env.step(Action::Straight);
+            total += reward;
+            steps += 1;
+            if done {
+                assert_eq!(info.death_cause, Some(DeathCause::Wall));
+                assert_eq!(reward, -1.01);
+                break;
+            }
+        }
+        assert!(total < 0.0);
+        assert!(steps <= 10);
+    }
+
+    #[test]
+    fn eating_an_apple_is_rewarded() {
+        let mut env = Env::new(EnvOptions::default());
+        env.reset(3);
+        let mut autopilot = Autopilot::new();
+        loop {
+            let state = env.get_state();
+            let heading = state.get_snek().get_head().get_direction();
+            let direction = autopilot.steer(state, 0).unwrap_or(heading);
+            let action = Action::ALL
+                .into_iter()
+                .find(|a| a.apply(heading) == direction)
+                .unwrap();
+            let (_, reward, done, info) = env.step(action);
+            if info.score > 0 {
+                assert_eq!(reward, 0.99);
+                break;
+            }
+            assert!(!done, "stopped before eating anything");
+        }
+    }
+
+    #[test]
+    fn episodes_are_cut_short_when_the_agent_dawdles() {
+        let mut env = Env::new(EnvOptions {
+            width: 6,
+            height: 6,
+            patience: Some(3),
+            game: GameOptions::wraparound(),
+            ..EnvOptions::default()
+        });
+        env.reset(0);
+        // going round and round a wraparound board never hits anything
+        let mut info = StepInfo::default();
+        let mut done = false;
+        while !done {
+            (_, _, done, info) = env.step(Action::Straight);
+        }
+        assert!(info.truncated);
+        assert_eq!(info.tick, 4);
+        assert!(env.is_done());
+    }
+
+    #[test]
+    fn the_same_seed_starts_the_same_game() {
+        let mut env = Env::new(EnvOptions::default());
+        let first = env.reset(9);
+        env.step(Action::Left);
+        assert_eq!(env.reset(9), first);
+        assert_eq!(env.get_state().get_tick(), 0);
+    }
+}
@@ -0,0 +1,9 @@
+mod batch;
+mod env;
+mod observation;
+mod rewards;
+
+pub use batch::BatchEnv;
+pub use env::{Action, Env, EnvOptions, Observation, StepInfo};
+pub use observation::{ObservationKind, EGOCENTRIC_FEATURES, PLANES};
+pub use rewards::Rewards;
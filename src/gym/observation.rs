@@ -0,0 +1,225 @@
+use crate::core::{AppleKind, Direction, Position, State};
+
+/// how many planes a [`ObservationKind::Planes`] observation has
+pub const PLANES: usize = 4;
+/// how many numbers a [`ObservationKind::Egocentric`] observation has
+pub const EGOCENTRIC_FEATURES: usize = 11;
+
+/// what an agent gets to see of the board each tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObservationKind {
+    /// the whole board as four width x height planes, one after another:
+    /// walls, snek bodies, the agent's head, and apples. cells are 1.0
+    /// where there's something there and 0.0 where there isn't, except
+    /// poison apples, which are -1.0 on the apple plane.
+    #[default]
+    Planes,
+    /// a handful of numbers about what's around the head, relative to the
+    /// way the snek is heading, so they don't depend on the board's size:
+    ///
+    /// - whether it would die going straight, left or right
+    /// - whether the nearest apple is ahead, behind, to the left or to the right
+    /// - how far it could go straight, left or right before hitting
+    ///   something, as a fraction of the board's longer side
+    /// - how much of the board the snek fills
+    Egocentric,
+}
+
+impl ObservationKind {
+    /// how many numbers an observation of a `width` x `height` board has
+    pub fn len(&self, width: usize, height: usize) -> usize {
+        match self {
+            ObservationKind::Planes => PLANES * width * height,
+            ObservationKind::Egocentric => EGOCENTRIC_FEATURES,
+        }
+    }
+
+    /// writes what `player` can see into `out`, which has to be exactly
+    /// [`ObservationKind::len`] long. everything in it gets overwritten.
+    pub fn write(&self, state: &State, player: usize, out: &mut [f32]) {
+        let (width, height) = state.get_walls().get_dimensions();
+        assert_eq!(
+            out.len(),
+            self.len(width, height),
+            "observation buffer is the wrong size"
+        );
+        match self {
+            ObservationKind::Planes => write_planes(state, player, out),
+            ObservationKind::Egocentric => write_egocentric(state, player, out),
+        }
+    }
+}
+
+fn write_planes(state: &State, player: usize, out: &mut [f32]) {
+    let walls = state.get_walls();
+    let (width, height) = walls.get_dimensions();
+    let cells = width * height;
+    out.fill(0.0);
+    let (wall_plane, rest) = out.split_at_mut(cells);
+    let (body_plane, rest) = rest.split_at_mut(cells);
+    let (head_plane, apple_plane) = rest.split_at_mut(cells);
+    for (i, cell) in wall_plane.iter_mut().enumerate() {
+        if walls.is_wall(&Position::new(i % width, i / width)) {
+            *cell = 1.0;
+        }
+    }
+    let index = |position: Position| {
+        let (x, y) = position.get_coordinates();
+        x + y * width
+    };
+    for (other, p) in state.get_players().iter().enumerate() {
+        if !p.is_alive() {
+            continue;
+        }
+        let snek = p.get_snek();
+        for segment in snek.get_tail() {
+            body_plane[index(segment.get_position())] = 1.0;
+        }
+        let head = snek.get_head().get_position();
+        if other == player {
+            head_plane[index(head)] = 1.0;
+        } else {
+            body_plane[index(head)] = 1.0;
+        }
+    }
+    for apple in state.get_apples() {
+        apple_plane[index(apple.get_position())] = match apple.get_kind() {
+            AppleKind::Poison => -1.0,
+            _ => 1.0,
+        };
+    }
+}
+
+fn write_egocentric(state: &State, player: usize, out: &mut [f32]) {
+    let walls = state.get_walls();
+    let (width, height) = walls.get_dimensions();
+    let snek = state.get_players()[player].get_snek();
+    let head = snek.get_head();
+    let position = head.get_position();
+    let ahead = head.get_direction();
+    let looking = [ahead, ahead.left(), ahead.right()];
+    for (i, direction) in looking.into_iter().enumerate() {
+        let next = walls.neighbor(&position, direction);
+        out[i] = match next {
+            Some(next) if !is_deadly(state, &next) => 0.0,
+            _ => 1.0,
+        };
+    }
+    // which way the nearest apple is, ignoring any shortcuts round the edges
+    let (x, y) = position.get_coordinates();
+    let apple = state
+        .get_apples()
+        .iter()
+        .filter(|a| a.get_kind() != AppleKind::Poison)
+        .map(|a| {
+            let (apple_x, apple_y) = a.get_position().get_coordinates();
+            (apple_x as isize - x as isize, apple_y as isize - y as isize)
+        })
+        .min_by_key(|(dx, dy)| dx.abs() + dy.abs());
+    let (forward, leftward) = match apple {
+        Some((dx, dy)) => (along(dx, dy, ahead), along(dx, dy, ahead.left())),
+        None => (0, 0),
+    };
+    out[3] = (forward > 0) as u8 as f32;
+    out[4] = (forward < 0) as u8 as f32;
+    out[5] = (leftward > 0) as u8 as f32;
+    out[6] = (leftward < 0) as u8 as f32;
+    let furthest = width.max(height);
+    for (i, direction) in looking.into_iter().enumerate() {
+        let mut room = 0;
+        let mut at = position;
+        while room < furthest {
+            match walls.neighbor(&at, direction) {
+                Some(next) if !is_deadly(state, &next) => {
+                    room += 1;
+                    at = next;
+                }
+                _ => break,
+            }
+        }
+        out[7 + i] = room as f32 / furthest as f32;
+    }
+    out[10] = snek.count_segments() as f32 / walls.get_max_segments().max(1) as f32;
+}
+
+/// how far `(dx, dy)` goes in `direction`
+fn along(dx: isize, dy: isize, direction: Direction) -> isize {
+    match direction {
+        Direction::Up => -dy,
+        Direction::Down => dy,
+        Direction::Left => -dx,
+        Direction::Right => dx,
+    }
+}
+
+/// whether slithering into `position` would kill a snek, not counting power-ups
+fn is_deadly(state: &State, position: &Position) -> bool {
+    state.is_wall(position)
+        || state
+            .get_players()
+            .iter()
+            .filter(|p| p.is_alive())
+            .any(|p| {
+                let snek = p.get_snek();
+                snek.get_head().get_position() == *position
+                    || snek
+                        .get_tail()
+                        .iter()
+                        .any(|s| s.get_position() == *position)
+            })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Apple, GameOptions, Snek, Walls};
+
+    fn corner_state() -> State {
+        // a snek heading up into the top wall with an apple off to its right
+        let snek = Snek::line_snek(Position::new(1, 3), Direction::Up, 2);
+        let apples = vec![Apple::new(Position::new(4, 2))];
+        State::from_parts(
+            Walls::new(6, 6),
+            vec![snek],
+            apples,
+            1,
+            GameOptions::default(),
+        )
+    }
+
+    #[test]
+    fn planes_show_everything_where_it_is() {
+        let state = corner_state();
+        let kind = ObservationKind::Planes;
+        let mut out = vec![0.0; kind.len(6, 6)];
+        kind.write(&state, 0, &mut out);
+        let cell = |plane: usize, x: usize, y: usize| out[plane * 36 + x + y * 6];
+        assert_eq!(cell(0, 0, 0), 1.0);
+        assert_eq!(cell(0, 2, 2), 0.0);
+        assert_eq!(cell(1, 1, 3), 1.0);
+        assert_eq!(cell(1, 1, 2), 0.0);
+        assert_eq!(cell(2, 1, 2), 1.0);
+        assert_eq!(cell(3, 4, 2), 1.0);
+        assert_eq!(out.iter().filter(|c| **c != 0.0).count(), 20 + 1 + 1 + 1);
+    }
+
+    #[test]
+    fn egocentric_observations_are_relative_to_the_head() {
+        let state = corner_state();
+        let kind = ObservationKind::Egocentric;
+        let mut out = vec![0.0; kind.len(6, 6)];
+        kind.write(&state, 0, &mut out);
+        // a wall to the left, the way ahead clear for one more cell, and the apple to the right
+        assert_eq!(&out[0..3], &[0.0, 1.0, 0.0]);
+        assert_eq!(&out[3..7], &[0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(out[7], 1.0 / 6.0);
+        assert_eq!(out[9], 3.0 / 6.0);
+        assert_eq!(out[10], 2.0 / 16.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong size")]
+    fn the_buffer_has_to_fit() {
+        ObservationKind::Egocentric.write(&corner_state(), 0, &mut [0.0; 3]);
+    }
+}
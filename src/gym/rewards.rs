@@ -0,0 +1,24 @@
+/// how much an agent is rewarded or punished for what happens each tick.
+/// every tick's reward is the sum of whichever of these apply.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rewards {
+    /// for every point scored, so a golden apple is worth more than a plain one
+    pub apple: f32,
+    /// for the tick the snek dies on
+    pub death: f32,
+    /// for every tick, usually a small cost so dawdling doesn't pay
+    pub tick: f32,
+    /// for eating the world
+    pub win: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            apple: 1.0,
+            death: -1.0,
+            tick: -0.01,
+            win: 10.0,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 pub mod core;
+pub mod gym;
+pub mod net;
 
 #[cfg(feature = "tui")]
 pub mod tui;
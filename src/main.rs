@@ -1,25 +1,256 @@
-use snek_rs::tui::Tui;
+use snek_rs::{
+    core::{simulate, Bot, GameOptions, SimulationOptions, State},
+    net::{Client, OnDisconnect, Peer, Server, ServerOptions, DEFAULT_PORT},
+    tui::Tui,
+};
 
+use structopt::StructOpt;
 use tracing::Level;
 use tracing_subscriber::fmt::format::FmtSpan;
 
-use std::panic;
+use std::{
+    net::{ToSocketAddrs, UdpSocket},
+    panic, process,
+    time::Duration,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "snek", about = "a rust-flavored terminal game")]
+struct Snek {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Host a game that anyone can join with `snek connect`
+    Serve {
+        /// The port to listen on
+        #[structopt(long, default_value = "7878")]
+        port: u16,
+
+        /// How wide the board is
+        #[structopt(long, default_value = "30")]
+        width: usize,
+
+        /// How tall the board is
+        #[structopt(long, default_value = "20")]
+        height: usize,
+
+        /// What happens to a snek when its player disconnects: `die` or `bot`
+        #[structopt(long, default_value = "die")]
+        on_disconnect: OnDisconnect,
+    },
+
+    /// Join a game hosted with `snek serve`
+    Connect {
+        /// The server's address, like `localhost` or `192.168.1.5:7878`
+        addr: String,
+    },
+
+    /// Watch a bot play
+    Watch {
+        /// Which bot plays: `autopilot`, `hamiltonian` or `greedy`
+        #[structopt(long, default_value = "autopilot")]
+        bot: Bot,
+    },
+
+    /// Play lots of games with a bot, without drawing them, and see how it did
+    Simulate {
+        /// Which bot plays: `autopilot`, `hamiltonian` or `greedy`
+        #[structopt(long, default_value = "autopilot")]
+        bot: Bot,
+
+        /// How many games to play
+        #[structopt(long, default_value = "1000")]
+        games: usize,
+
+        /// How wide the board is
+        #[structopt(long, default_value = "20")]
+        width: usize,
+
+        /// How tall the board is
+        #[structopt(long, default_value = "20")]
+        height: usize,
+
+        /// The first game's seed, with each game after it getting the next one
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+
+        /// How many ticks the bot can go without scoring before giving up on it.
+        /// Defaults to twice the number of cells on the board
+        #[structopt(long)]
+        patience: Option<usize>,
+
+        /// Print the stats as json
+        #[structopt(long)]
+        json: bool,
+    },
+
+    /// Play a two-player game straight against someone else, without a server
+    Peer {
+        /// The other peer's address, like `192.168.1.5:7879`
+        addr: String,
+
+        /// The port to listen on
+        #[structopt(long, default_value = "7879")]
+        port: u16,
+
+        /// Host the game, rather than join the one the other peer is hosting
+        #[structopt(long)]
+        host: bool,
+
+        /// How wide the board is, if hosting
+        #[structopt(long, default_value = "30")]
+        width: usize,
+
+        /// How tall the board is, if hosting
+        #[structopt(long, default_value = "20")]
+        height: usize,
+    },
+}
 
 fn main() {
-    panic::set_hook(Box::new(|e| {
-        tracing::info!("panicked:\n{:?}", e);
-    }));
     let current_dir = std::env::current_dir().unwrap();
     let log_file = "tui-snek.log";
     let file_appender = tracing_appender::rolling::never(current_dir, log_file);
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
     let format = tracing_subscriber::fmt::format().without_time().pretty();
+    let command = Snek::from_args().command;
+    // a game in the terminal would draw right over anything printed,
+    // but everything else can say why it crashed
+    let headless = matches!(
+        command,
+        Some(Command::Simulate { .. } | Command::Serve { .. })
+    );
+    panic::set_hook(Box::new(move |e| {
+        tracing::error!("panicked:\n{:?}", e);
+        if headless {
+            eprintln!("{}", e);
+        }
+    }));
+    // thousands of games at once would fill the log up in no time
+    let max_level = match command {
+        Some(Command::Simulate { .. }) => Level::WARN,
+        _ => Level::INFO,
+    };
     tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
+        .with_max_level(max_level)
         .event_format(format)
         .with_writer(non_blocking)
         .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
         .init();
-    let mut tui = Tui::new();
-    tui.run()
+    match command {
+        None => Tui::new().run(),
+        Some(Command::Serve {
+            port,
+            width,
+            height,
+            on_disconnect,
+        }) => {
+            check_board_size(width, height);
+            let options = ServerOptions {
+                width,
+                height,
+                on_disconnect,
+                ..ServerOptions::default()
+            };
+            let mut server = Server::bind(("0.0.0.0", port), options).unwrap_or_else(|e| {
+                eprintln!("could not ssserve on port {}: {}", port, e);
+                process::exit(1);
+            });
+            println!("ssserving on {}", server.local_addr());
+            server.run();
+        }
+        Some(Command::Connect { addr }) => {
+            let addr = if addr.contains(':') {
+                addr
+            } else {
+                format!("{}:{}", addr, DEFAULT_PORT)
+            };
+            let client = Client::connect(&addr).unwrap_or_else(|e| {
+                eprintln!("could not connect to {}: {}", addr, e);
+                process::exit(1);
+            });
+            Tui::connect(client).run();
+        }
+        Some(Command::Watch { bot }) => Tui::watch(bot).run(),
+        Some(Command::Simulate {
+            bot,
+            games,
+            width,
+            height,
+            seed,
+            patience,
+            json,
+        }) => {
+            check_board_size(width, height);
+            let options = SimulationOptions {
+                bot,
+                games,
+                width,
+                height,
+                seed,
+                patience,
+                ..SimulationOptions::default()
+            };
+            let stats = simulate(options).unwrap_or_else(|e| {
+                eprintln!("the {} bot can't play: {}", bot.describe(), e);
+                process::exit(1);
+            });
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+            } else {
+                print!("{}", stats);
+            }
+        }
+        Some(Command::Peer {
+            addr,
+            port,
+            host,
+            width,
+            height,
+        }) => {
+            if host {
+                check_board_size(width, height);
+            }
+            let peer = connect_peer(&addr, port, host, width, height).unwrap_or_else(|e| {
+                eprintln!("could not play against {}: {}", addr, e);
+                process::exit(1);
+            });
+            Tui::play_peer(peer).run();
+        }
+    }
+}
+
+/// games can't be played on a board that's too small,
+/// so this exits rather than letting the game panic
+fn check_board_size(width: usize, height: usize) {
+    if let Err(e) = State::check_board_size(width, height) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn connect_peer(
+    addr: &str,
+    port: u16,
+    host: bool,
+    width: usize,
+    height: usize,
+) -> std::io::Result<Peer> {
+    let remote = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no such address"))?;
+    let socket = UdpSocket::bind(("0.0.0.0", port))?;
+    let timeout = Duration::from_secs(60);
+    if host {
+        println!("waiting for {} to join...", remote);
+        let options = GameOptions::two_player();
+        Peer::host(socket, remote, width, height, options, timeout)
+    } else {
+        println!("joining {}...", remote);
+        Peer::join(socket, remote, timeout)
+    }
 }
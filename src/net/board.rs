@@ -0,0 +1,111 @@
+use crate::core::{Apple, Position, PowerUpKind, Segment, State};
+
+use serde::{Deserialize, Serialize};
+
+/// what's on one cell of the board, apart from walls,
+/// which never change and are sent along with the game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Tile {
+    Free,
+    Snek(Segment),
+    Apple(Apple),
+    PowerUp(PowerUpKind),
+}
+
+/// everything a client needs to draw the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    width: usize,
+    tiles: Vec<Tile>,
+}
+
+impl Board {
+    /// the board as it is in `state`. only sneks that are
+    /// still alive are on it, so they can be slithered over.
+    pub fn new(state: &State) -> Self {
+        let (width, height) = state.get_walls().get_dimensions();
+        let mut board = Board {
+            width,
+            tiles: vec![Tile::Free; width * height],
+        };
+        for apple in state.get_apples() {
+            board.set(apple.get_position(), Tile::Apple(*apple));
+        }
+        for power_up in state.get_power_ups() {
+            board.set(power_up.get_position(), Tile::PowerUp(power_up.get_kind()));
+        }
+        for player in state.get_players().iter().filter(|p| p.is_alive()) {
+            for segment in player.get_snek().get_segments() {
+                board.set(segment.get_position(), Tile::Snek(segment));
+            }
+        }
+        board
+    }
+
+    pub fn get(&self, position: Position) -> Tile {
+        self.tiles[self.get_idx(position)]
+    }
+
+    pub fn set(&mut self, position: Position, tile: Tile) {
+        let i = self.get_idx(position);
+        self.tiles[i] = tile;
+    }
+
+    /// every tile, along with where it is
+    pub fn get_tiles(&self) -> impl Iterator<Item = (Position, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| (self.get_position(i), *tile))
+    }
+
+    /// the tiles that are different on `next`, which must be the same size
+    pub fn diff(&self, next: &Board) -> Vec<(Position, Tile)> {
+        if self.tiles.len() != next.tiles.len() {
+            panic!("can only diff boards that are the same size");
+        }
+        self.tiles
+            .iter()
+            .zip(&next.tiles)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(i, (_, new))| (self.get_position(i), *new))
+            .collect()
+    }
+
+    /// applies changes from [`Board::diff`]
+    pub fn apply(&mut self, changes: &[(Position, Tile)]) {
+        for (position, tile) in changes {
+            self.set(*position, *tile);
+        }
+    }
+
+    fn get_idx(&self, position: Position) -> usize {
+        let (x, y) = position.get_coordinates();
+        x + self.width * y
+    }
+
+    fn get_position(&self, i: usize) -> Position {
+        Position::new(i % self.width, i / self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_a_diff_catches_a_board_up() {
+        let mut state = State::with_seed(10, 10, 4);
+        let before = Board::new(&state);
+        state.tick();
+        state.tick();
+        let after = Board::new(&state);
+        let changes = before.diff(&after);
+        assert!(!changes.is_empty());
+        let mut caught_up = before.clone();
+        caught_up.apply(&changes);
+        assert_eq!(caught_up, after);
+        assert!(after.diff(&after).is_empty());
+    }
+}
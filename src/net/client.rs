@@ -0,0 +1,78 @@
+use crate::core::Direction;
+use crate::net::protocol::{self, ClientMessage, ServerMessage};
+
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, TryRecvError};
+
+use std::{
+    io::{self, BufReader},
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    thread,
+    time::Duration,
+};
+
+/// a connection to a [`Server`](crate::net::Server). messages from the
+/// server are read in the background so the game never waits on the network.
+pub struct Client {
+    writer: TcpStream,
+    messages: Receiver<ServerMessage>,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Client> {
+        let writer = TcpStream::connect(addr)?;
+        writer.set_nodelay(true)?;
+        let mut reader = BufReader::new(writer.try_clone()?);
+        let (sender, messages) = unbounded();
+        thread::spawn(move || loop {
+            match protocol::receive(&mut reader) {
+                Ok(Some(message)) => {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::info!("could not read from the server: {}", e);
+                    break;
+                }
+            }
+        });
+        Ok(Client { writer, messages })
+    }
+
+    /// asks the server to turn this client's snek
+    pub fn turn(&mut self, direction: Direction) -> io::Result<()> {
+        protocol::send(&mut self.writer, &ClientMessage::Turn(direction))
+    }
+
+    /// the next message from the server, if one has arrived
+    pub fn try_receive(&self) -> io::Result<Option<ServerMessage>> {
+        match self.messages.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(hung_up()),
+        }
+    }
+
+    /// waits up to `timeout` for the next message from the server
+    pub fn receive_timeout(&self, timeout: Duration) -> io::Result<ServerMessage> {
+        self.messages.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => {
+                io::Error::new(io::ErrorKind::TimedOut, "the server went quiet")
+            }
+            RecvTimeoutError::Disconnected => hung_up(),
+        })
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // the background reader has its own handle on the
+        // connection, so it has to be closed on purpose
+        let _ = self.writer.shutdown(Shutdown::Both);
+    }
+}
+
+fn hung_up() -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionAborted, "the server hung up")
+}
@@ -0,0 +1,11 @@
+mod board;
+mod client;
+mod peer;
+mod protocol;
+mod server;
+
+pub use board::{Board, Tile};
+pub use client::Client;
+pub use peer::{Desync, Peer, MAX_PREDICTION};
+pub use protocol::{BoardDelta, ClientMessage, PlayerStatus, ServerMessage};
+pub use server::{OnDisconnect, Server, ServerOptions, DEFAULT_PORT};
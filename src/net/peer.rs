@@ -0,0 +1,503 @@
+use crate::core::{Direction, GameEvent, GameOptions, SlitherResult, Snapshot, State};
+
+use crossbeam_channel::Receiver;
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// how many ticks a peer will guess the other peer's turns for
+/// before it waits to hear what they actually did
+pub const MAX_PREDICTION: usize = 8;
+/// how many confirmed hashes are kept around to check against the other peer's
+const HASHES_KEPT: usize = 64;
+/// big enough for any message a peer sends
+const MAX_DATAGRAM: usize = 65_507;
+
+/// what one peer tells the other. every message
+/// is one datagram, and any of them can go missing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum PeerMessage {
+    /// the host's game, sent until the guest hears it
+    Start {
+        seed: u64,
+        width: usize,
+        height: usize,
+        options: GameOptions,
+    },
+    /// the guest heard the start
+    Ready,
+    /// every turn the other peer hasn't acknowledged yet,
+    /// one for each tick, starting at tick `first_tick`
+    Inputs {
+        first_tick: usize,
+        inputs: Vec<Option<Direction>>,
+        /// how many of the other peer's turns this peer has
+        ack: usize,
+        /// the hash of this peer's game after a tick both peers agree on
+        hash: Option<(usize, u64)>,
+    },
+}
+
+/// the two peers' games stopped matching
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Desync {
+    pub tick: usize,
+}
+
+impl Display for Desync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the games stopped matching at tick {}", self.tick)
+    }
+}
+
+impl std::error::Error for Desync {}
+
+/// one side of a two-player game played over udp without a server.
+/// both peers run the whole game, and only tell each other how
+/// they turned. a peer that hasn't heard from the other yet guesses
+/// the other snek kept going the same way, and if that guess turns
+/// out wrong it rewinds to the last tick both agree on and plays
+/// the game forward again.
+pub struct Peer {
+    socket: UdpSocket,
+    /// which snek is ours. the host is player one.
+    player: usize,
+    state: State,
+    /// every turn each player made, one for each tick
+    inputs: [Vec<Option<Direction>>; 2],
+    /// the game before each tick that isn't confirmed yet, oldest first
+    snapshots: VecDeque<Snapshot>,
+    /// where we'll turn on the next tick
+    next_turn: Option<Direction>,
+    /// how many of our turns the other peer has
+    acked: usize,
+    /// our hashes of confirmed ticks, oldest first
+    hashes: VecDeque<(usize, u64)>,
+    /// the other peer's hashes we haven't been able to check yet, oldest first
+    remote_hashes: VecDeque<(usize, u64)>,
+    rollbacks: usize,
+}
+
+impl Peer {
+    /// waits up to `timeout` for the other peer to join a new game
+    pub fn host(
+        socket: UdpSocket,
+        remote: SocketAddr,
+        width: usize,
+        height: usize,
+        options: GameOptions,
+        timeout: Duration,
+    ) -> io::Result<Peer> {
+        socket.connect(remote)?;
+        let seed = rand::random();
+        let start = PeerMessage::Start {
+            seed,
+            width,
+            height,
+            options,
+        };
+        let started = Instant::now();
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        loop {
+            send(&socket, &start)?;
+            // the guest starts ticking as soon as it hears the start, so its turns count as ready too
+            if let Some(PeerMessage::Ready | PeerMessage::Inputs { .. }) = receive(&socket)? {
+                break;
+            }
+            if started.elapsed() > timeout {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "nobody joined the game",
+                ));
+            }
+        }
+        Peer::new(socket, 0, width, height, seed, options)
+    }
+
+    /// waits up to `timeout` to hear the game the other peer is hosting
+    pub fn join(socket: UdpSocket, remote: SocketAddr, timeout: Duration) -> io::Result<Peer> {
+        socket.connect(remote)?;
+        let started = Instant::now();
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        loop {
+            if let Some(PeerMessage::Start {
+                seed,
+                width,
+                height,
+                options,
+            }) = receive(&socket)?
+            {
+                send(&socket, &PeerMessage::Ready)?;
+                return Peer::new(socket, 1, width, height, seed, options);
+            }
+            if started.elapsed() > timeout {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "nobody is hosting a game",
+                ));
+            }
+        }
+    }
+
+    fn new(
+        socket: UdpSocket,
+        player: usize,
+        width: usize,
+        height: usize,
+        seed: u64,
+        options: GameOptions,
+    ) -> io::Result<Peer> {
+        socket.set_nonblocking(true)?;
+        let options = GameOptions {
+            players: 2,
+            ..options
+        };
+        Ok(Peer {
+            socket,
+            player,
+            state: State::with_options(width, height, seed, options),
+            inputs: [Vec::new(), Vec::new()],
+            snapshots: VecDeque::new(),
+            next_turn: None,
+            acked: 0,
+            hashes: VecDeque::new(),
+            remote_hashes: VecDeque::new(),
+            rollbacks: 0,
+        })
+    }
+
+    /// which snek is ours
+    pub fn get_player(&self) -> usize {
+        self.player
+    }
+
+    /// the game as far as this peer knows. it might
+    /// still change if the other peer turned unexpectedly.
+    pub fn get_state(&self) -> &State {
+        &self.state
+    }
+
+    /// starts listening to the game as this peer plays it. when a guess turns
+    /// out wrong, the ticks since are heard about again as they're played over.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        self.state.subscribe()
+    }
+
+    /// how many times this peer guessed wrong and had to play the game forward again
+    pub fn count_rollbacks(&self) -> usize {
+        self.rollbacks
+    }
+
+    /// turns our snek on the next tick
+    pub fn turn(&mut self, direction: Direction) {
+        self.next_turn = Some(direction);
+    }
+
+    /// whether both peers agree the game is over
+    pub fn is_game_over(&self) -> bool {
+        self.state.is_game_over() && self.count_confirmed() >= self.state.get_tick()
+    }
+
+    /// catches up with the other peer, then moves the game forward one
+    /// tick unless it's too far ahead of them or the game is over.
+    /// returns what that tick did to each player, if it ticked.
+    pub fn tick(&mut self) -> Result<Option<Vec<SlitherResult>>, Desync> {
+        self.receive_all();
+        let slither_results = if self.state.is_game_over()
+            || self.state.get_tick() >= self.inputs[self.remote()].len() + MAX_PREDICTION
+        {
+            None
+        } else {
+            let turn = self.next_turn.take();
+            self.inputs[self.player].push(turn);
+            Some(self.advance())
+        };
+        self.confirm();
+        self.send_inputs();
+        self.check_hashes()?;
+        Ok(slither_results)
+    }
+
+    fn remote(&self) -> usize {
+        1 - self.player
+    }
+
+    /// how many ticks both peers know each other's turns for
+    fn count_confirmed(&self) -> usize {
+        self.inputs[0].len().min(self.inputs[1].len())
+    }
+
+    /// plays the next tick with everyone's turns, guessing
+    /// the other peer didn't turn if we haven't heard yet
+    fn advance(&mut self) -> Vec<SlitherResult> {
+        let tick = self.state.get_tick();
+        self.snapshots.push_back(self.state.snapshot());
+        for player in 0..2 {
+            if let Some(Some(direction)) = self.inputs[player].get(tick) {
+                self.state.turn_player(player, *direction);
+            }
+        }
+        self.state.tick_all()
+    }
+
+    fn receive_all(&mut self) {
+        let mut first_surprise = None;
+        while let Ok(Some(message)) = receive(&self.socket) {
+            if let PeerMessage::Inputs {
+                first_tick,
+                inputs,
+                ack,
+                hash,
+            } = message
+            {
+                self.acked = self.acked.max(ack);
+                if let Some((tick, hash)) = hash {
+                    if self
+                        .remote_hashes
+                        .back()
+                        .is_none_or(|(known, _)| tick > *known)
+                    {
+                        self.remote_hashes.push_back((tick, hash));
+                    }
+                }
+                let remote = self.remote();
+                for (tick, input) in (first_tick..).zip(inputs) {
+                    if tick != self.inputs[remote].len() {
+                        continue;
+                    }
+                    // we guessed they didn't turn on ticks we've already played
+                    if input.is_some() && tick < self.state.get_tick() {
+                        first_surprise.get_or_insert(tick);
+                    }
+                    self.inputs[remote].push(input);
+                }
+            }
+        }
+        if let Some(tick) = first_surprise {
+            self.roll_back(tick);
+        }
+    }
+
+    /// goes back to before tick number `tick` and plays forward again
+    fn roll_back(&mut self, tick: usize) {
+        let now = self.state.get_tick();
+        let oldest = self.snapshots.front().map_or(now, |s| s.get_tick());
+        tracing::debug!("rolling back from tick {} to {}", now, tick);
+        let snapshot = self.snapshots[tick - oldest].clone();
+        self.snapshots.truncate(tick - oldest);
+        self.state.restore(&snapshot);
+        // the game might end sooner now that we know better
+        while self.state.get_tick() < now && !self.state.is_game_over() {
+            self.advance();
+        }
+        self.rollbacks += 1;
+    }
+
+    /// forgets snapshots from before the last tick both peers agree on,
+    /// hashing the game as it was after each one
+    fn confirm(&mut self) {
+        let confirmed = self.count_confirmed().min(self.state.get_tick());
+        while let Some(snapshot) = self.snapshots.front() {
+            let tick = snapshot.get_tick();
+            if tick >= confirmed {
+                break;
+            }
+            self.snapshots.pop_front();
+            let hash = match self.snapshots.front() {
+                Some(snapshot) => snapshot.get_hash(),
+                None => self.state.get_hash(),
+            };
+            self.hashes.push_back((tick + 1, hash));
+            if self.hashes.len() > HASHES_KEPT {
+                self.hashes.pop_front();
+            }
+        }
+    }
+
+    /// checks the other peer's hashes against ours, as far as we've got
+    fn check_hashes(&mut self) -> Result<(), Desync> {
+        while let Some((tick, remote_hash)) = self.remote_hashes.front().copied() {
+            match self.hashes.iter().find(|(t, _)| *t == tick) {
+                Some((_, hash)) if *hash != remote_hash => return Err(Desync { tick }),
+                Some(_) => {}
+                // we haven't got that far yet
+                None if self.hashes.back().is_none_or(|(t, _)| *t < tick) => break,
+                // it's too long ago to check
+                None => {}
+            }
+            self.remote_hashes.pop_front();
+        }
+        Ok(())
+    }
+
+    fn send_inputs(&self) {
+        let message = PeerMessage::Inputs {
+            first_tick: self.acked,
+            inputs: self.inputs[self.player][self.acked..].to_vec(),
+            ack: self.inputs[self.remote()].len(),
+            hash: self.hashes.back().copied(),
+        };
+        if let Err(e) = send(&self.socket, &message) {
+            tracing::info!("could not reach the other peer: {}", e);
+        }
+    }
+}
+
+fn send(socket: &UdpSocket, message: &PeerMessage) -> io::Result<()> {
+    let bytes = serde_json::to_vec(message).map_err(io::Error::from)?;
+    socket.send(&bytes)?;
+    Ok(())
+}
+
+/// the next message, or `None` if there isn't one waiting
+fn receive(socket: &UdpSocket) -> io::Result<Option<PeerMessage>> {
+    let mut buffer = [0; MAX_DATAGRAM];
+    match socket.recv(&mut buffer) {
+        Ok(len) => match serde_json::from_slice(&buffer[..len]) {
+            Ok(message) => Ok(Some(message)),
+            Err(e) => {
+                tracing::info!("ignoring a garbled message: {}", e);
+                Ok(None)
+            }
+        },
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(None)
+        }
+        // nobody listening on the other end yet
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn connect() -> (Peer, Peer) {
+        let host_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let guest_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let host_addr = host_socket.local_addr().unwrap();
+        let guest_addr = guest_socket.local_addr().unwrap();
+        let host = thread::spawn(move || {
+            Peer::host(
+                host_socket,
+                guest_addr,
+                16,
+                16,
+                GameOptions::default(),
+                TIMEOUT,
+            )
+            .unwrap()
+        });
+        let guest = Peer::join(guest_socket, host_addr, TIMEOUT).unwrap();
+        (host.join().unwrap(), guest)
+    }
+
+    /// ticks a peer until it's played `ticks` ticks, giving the other peer's messages time to arrive
+    fn tick_to(peer: &mut Peer, ticks: usize) {
+        let started = Instant::now();
+        while peer.get_state().get_tick() < ticks && !peer.get_state().is_game_over() {
+            assert!(started.elapsed() < TIMEOUT, "the other peer went quiet");
+            if peer.tick().unwrap().is_none() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    /// ticks both peers until they agree on everything they've played
+    fn settle(host: &mut Peer, guest: &mut Peer) {
+        let started = Instant::now();
+        while host.count_confirmed() < host.get_state().get_tick()
+            || guest.count_confirmed() < guest.get_state().get_tick()
+        {
+            assert!(started.elapsed() < TIMEOUT, "the peers never agreed");
+            host.receive_all();
+            host.confirm();
+            host.send_inputs();
+            guest.receive_all();
+            guest.confirm();
+            guest.send_inputs();
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn both_peers_play_the_same_game() {
+        let (mut host, mut guest) = connect();
+        assert_eq!((host.get_player(), guest.get_player()), (0, 1));
+        assert_eq!(host.get_state(), guest.get_state());
+        host.turn(Direction::Down);
+        guest.turn(Direction::Up);
+        for ticks in 1..=6 {
+            tick_to(&mut host, ticks);
+            tick_to(&mut guest, ticks);
+        }
+        settle(&mut host, &mut guest);
+        assert_eq!(host.get_state().get_hash(), guest.get_state().get_hash());
+        assert_eq!(host.get_state().get_direction(), Direction::Down);
+    }
+
+    #[test]
+    fn a_late_turn_rolls_the_game_back() {
+        let (mut host, mut guest) = connect();
+        // the host races ahead, guessing the guest keeps going left
+        tick_to(&mut host, MAX_PREDICTION);
+        assert!(
+            host.tick().unwrap().is_none(),
+            "the host should wait for the guest"
+        );
+        guest.turn(Direction::Up);
+        tick_to(&mut guest, MAX_PREDICTION);
+        settle(&mut host, &mut guest);
+        assert!(host.count_rollbacks() > 0);
+        assert_eq!(guest.count_rollbacks(), 0);
+        assert_eq!(host.get_state().get_hash(), guest.get_state().get_hash());
+        // and it ends up just where it would have without the network
+        let mut expected = State::with_options(
+            16,
+            16,
+            host.get_state().get_seed(),
+            host.get_state().get_options(),
+        );
+        expected.turn_player(1, Direction::Up);
+        for _ in 0..MAX_PREDICTION {
+            expected.tick_all();
+        }
+        assert_eq!(host.get_state().get_hash(), expected.get_hash());
+    }
+
+    #[test]
+    fn it_notices_when_the_games_stop_matching() {
+        let (mut host, mut guest) = connect();
+        tick_to(&mut host, 2);
+        tick_to(&mut guest, 2);
+        settle(&mut host, &mut guest);
+        host.hashes.iter_mut().for_each(|(_, hash)| *hash += 1);
+        let started = Instant::now();
+        loop {
+            assert!(started.elapsed() < TIMEOUT, "the desync went unnoticed");
+            host.send_inputs();
+            if let Err(desync) = guest.tick() {
+                assert!(desync.tick <= 2);
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::core::{DeathCause, Direction, Position, State};
+use crate::net::Tile;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::io::{self, BufRead, Write};
+
+/// what a client tells the server
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// turn this client's snek
+    Turn(Direction),
+}
+
+/// what the server tells its clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// the whole game, sent when a client joins and whenever a new
+    /// round starts, along with which snek is theirs. clients who
+    /// join between rounds or find the board too full to fit
+    /// another snek in watch until the next round.
+    Welcome {
+        player: Option<usize>,
+        state: Box<State>,
+    },
+    /// what changed on the last tick
+    Tick(BoardDelta),
+}
+
+/// everything that changed on the board in one tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardDelta {
+    pub tick: usize,
+    pub changes: Vec<(Position, Tile)>,
+    /// every player, player one first
+    pub players: Vec<PlayerStatus>,
+    /// whether the round is over. the server
+    /// starts another one after a little while.
+    pub game_over: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStatus {
+    pub score: usize,
+    pub death_cause: Option<DeathCause>,
+    /// whether nobody is steering this snek anymore,
+    /// so the server is steering it instead
+    pub bot: bool,
+}
+
+/// writes one message as a line of json
+pub(crate) fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, message).map_err(io::Error::from)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+/// reads the next line of json, or `None` once the other side hangs up
+pub(crate) fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(io::Error::from)
+}
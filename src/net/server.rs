@@ -0,0 +1,527 @@
+use crate::core::{Controller, DeathCause, Direction, GameOptions, Greedy, State};
+use crate::net::{
+    protocol::{self, BoardDelta, ClientMessage, PlayerStatus, ServerMessage},
+    Board,
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+use std::{
+    io::{self, BufReader},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// the port `snek serve` listens on if it isn't told otherwise
+pub const DEFAULT_PORT: u16 = 7878;
+/// how many ticks the server waits between one round ending and the next starting
+const ROUND_BREAK_TICKS: usize = 18;
+
+/// what happens to a snek when whoever was steering it disconnects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnDisconnect {
+    /// the snek dies on the spot
+    #[default]
+    Die,
+    /// the server steers the snek for the rest of the round
+    Bot,
+}
+
+impl FromStr for OnDisconnect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "die" => Ok(OnDisconnect::Die),
+            "bot" => Ok(OnDisconnect::Bot),
+            _ => Err(format!("expected `die` or `bot`, got `{}`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServerOptions {
+    pub width: usize,
+    pub height: usize,
+    /// the rules every round is played by. the number of
+    /// players is ignored, since everyone who connects plays.
+    pub game: GameOptions,
+    pub on_disconnect: OnDisconnect,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            width: 30,
+            height: 20,
+            game: GameOptions::default(),
+            on_disconnect: OnDisconnect::default(),
+        }
+    }
+}
+
+/// who's steering each snek in the current round
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Seat {
+    /// the client with this connection id
+    Human(usize),
+    /// the server, because its client went away
+    Bot,
+    /// nobody, because its client went away and it died
+    Empty,
+}
+
+/// what the network threads tell the game loop
+enum Event {
+    Joined(usize, TcpStream),
+    Turned(usize, Direction),
+    Left(usize),
+}
+
+struct Connection {
+    id: usize,
+    writer: TcpStream,
+}
+
+/// runs the one true game that every [`Client`](crate::net::Client) sees.
+/// clients can join and leave at any time; whoever joins partway
+/// through a round gets a new snek somewhere out of the way.
+pub struct Server {
+    local_addr: SocketAddr,
+    options: ServerOptions,
+    events: Receiver<Event>,
+    connections: Vec<Connection>,
+    state: Option<State>,
+    seats: Vec<Seat>,
+    board: Option<Board>,
+    /// ticks left before the next round starts, if this one is over
+    round_break: Option<usize>,
+}
+
+impl Server {
+    /// starts listening for clients in the background. a board
+    /// too small to play on is [`io::ErrorKind::InvalidInput`].
+    pub fn bind(addr: impl ToSocketAddrs, options: ServerOptions) -> io::Result<Server> {
+        State::check_board_size(options.width, options.height)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let (sender, events) = unbounded();
+        thread::spawn(move || accept(listener, sender));
+        Ok(Server {
+            local_addr,
+            options,
+            events,
+            connections: Vec::new(),
+            state: None,
+            seats: Vec::new(),
+            board: None,
+            round_break: None,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// the game being played, if anybody is connected
+    pub fn get_state(&self) -> Option<&State> {
+        self.state.as_ref()
+    }
+
+    pub fn count_connections(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// ticks forever, at whatever speed the game is going
+    pub fn run(&mut self) {
+        loop {
+            let started = Instant::now();
+            self.handle_events();
+            self.tick();
+            // with nobody connected there's no game, so wait as long as a new one's first tick
+            let tick_length = match &self.state {
+                Some(state) => state.get_tick_duration(),
+                None => self.options.game.speed.get_tick_duration(0),
+            };
+            thread::sleep(tick_length.saturating_sub(started.elapsed()));
+        }
+    }
+
+    /// deals with everyone who joined, turned or left since last time
+    pub fn handle_events(&mut self) {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Event::Joined(id, writer) => self.join(id, writer),
+                Event::Turned(id, direction) => {
+                    let seat = self.seats.iter().position(|s| *s == Seat::Human(id));
+                    if let (Some(state), Some(player)) = (&mut self.state, seat) {
+                        state.turn_player(player, direction);
+                    }
+                }
+                Event::Left(id) => self.leave(id),
+            }
+        }
+    }
+
+    /// moves the game forward one tick and tells everyone what changed
+    pub fn tick(&mut self) {
+        match self.round_break {
+            Some(0) => {
+                self.round_break = None;
+                self.start_round();
+                return;
+            }
+            Some(ticks_left) => {
+                self.round_break = Some(ticks_left - 1);
+                return;
+            }
+            None => {}
+        }
+        let state = match &mut self.state {
+            Some(state) => state,
+            None => return,
+        };
+        for (player, seat) in self.seats.iter().enumerate() {
+            if let Seat::Bot = seat {
+                if let Some(direction) = Greedy.steer(state, player) {
+                    state.turn_player(player, direction);
+                }
+            }
+        }
+        state.tick_all();
+        let board = Board::new(state);
+        let changes = match &self.board {
+            Some(old_board) => old_board.diff(&board),
+            None => Vec::new(),
+        };
+        let delta = BoardDelta {
+            tick: state.get_tick(),
+            changes,
+            players: state
+                .get_players()
+                .iter()
+                .zip(&self.seats)
+                .map(|(player, seat)| PlayerStatus {
+                    score: player.get_score(),
+                    death_cause: player.get_death_cause(),
+                    bot: *seat == Seat::Bot,
+                })
+                .collect(),
+            game_over: state.is_game_over(),
+        };
+        if delta.game_over {
+            tracing::info!("round over after {} ticks", delta.tick);
+            self.round_break = Some(ROUND_BREAK_TICKS);
+        }
+        self.board = Some(board);
+        self.broadcast(&ServerMessage::Tick(delta));
+    }
+
+    fn join(&mut self, id: usize, writer: TcpStream) {
+        tracing::info!("client {} connected", id);
+        self.connections.push(Connection { id, writer });
+        if self.state.is_none() {
+            self.start_round();
+            return;
+        }
+        let mut player = None;
+        if self.round_break.is_none() {
+            let state = self.state.as_mut().unwrap();
+            player = state.add_player();
+            if player.is_some() {
+                self.seats.push(Seat::Human(id));
+            }
+        }
+        self.welcome(id, player);
+    }
+
+    fn leave(&mut self, id: usize) {
+        tracing::info!("client {} disconnected", id);
+        self.connections.retain(|c| c.id != id);
+        if self.connections.is_empty() {
+            // nobody is watching, so wait for someone to join before playing again
+            self.state = None;
+            self.seats.clear();
+            self.board = None;
+            self.round_break = None;
+            return;
+        }
+        if let Some(player) = self.seats.iter().position(|s| *s == Seat::Human(id)) {
+            match self.options.on_disconnect {
+                OnDisconnect::Die => {
+                    self.seats[player] = Seat::Empty;
+                    if let Some(state) = &mut self.state {
+                        state.kill_player(player, DeathCause::Disconnected);
+                    }
+                }
+                OnDisconnect::Bot => self.seats[player] = Seat::Bot,
+            }
+        }
+    }
+
+    /// starts a fresh game with a snek for everyone connected
+    fn start_round(&mut self) {
+        let options = GameOptions {
+            players: 1,
+            ..self.options.game
+        };
+        let mut state = State::with_options(
+            self.options.width,
+            self.options.height,
+            rand::random(),
+            options,
+        );
+        self.seats.clear();
+        let ids: Vec<usize> = self.connections.iter().map(|c| c.id).collect();
+        let mut players = Vec::with_capacity(ids.len());
+        for (i, id) in ids.iter().enumerate() {
+            let player = if i == 0 { Some(0) } else { state.add_player() };
+            if player.is_some() {
+                self.seats.push(Seat::Human(*id));
+            }
+            players.push(player);
+        }
+        tracing::info!("starting a round with {} players", self.seats.len());
+        self.board = Some(Board::new(&state));
+        self.state = Some(state);
+        for (id, player) in ids.into_iter().zip(players) {
+            self.welcome(id, player);
+        }
+    }
+
+    fn welcome(&mut self, id: usize, player: Option<usize>) {
+        let welcome = ServerMessage::Welcome {
+            player,
+            state: Box::new(self.state.as_ref().unwrap().redact()),
+        };
+        let connection = self.connections.iter_mut().find(|c| c.id == id).unwrap();
+        if let Err(e) = protocol::send(&mut connection.writer, &welcome) {
+            tracing::info!("could not welcome client {}: {}", id, e);
+            self.leave(id);
+        }
+    }
+
+    /// sends a message to every client, and drops any that can't keep up
+    fn broadcast(&mut self, message: &ServerMessage) {
+        let mut gone = Vec::new();
+        for connection in &mut self.connections {
+            if let Err(e) = protocol::send(&mut connection.writer, message) {
+                tracing::info!("could not reach client {}: {}", connection.id, e);
+                gone.push(connection.id);
+            }
+        }
+        for id in gone {
+            self.leave(id);
+        }
+    }
+}
+
+/// hands every new connection to the game loop, along with
+/// a thread that passes on whatever the client sends
+fn accept(listener: TcpListener, events: Sender<Event>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::info!("could not accept a client: {}", e);
+                continue;
+            }
+        };
+        let reader = match stream.try_clone() {
+            Ok(reader) => reader,
+            Err(e) => {
+                tracing::info!("could not read from client {}: {}", id, e);
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        // a client that stops reading shouldn't stop the game for everyone else
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+        if events.send(Event::Joined(id, stream)).is_err() {
+            return;
+        }
+        let events = events.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            while let Ok(Some(ClientMessage::Turn(direction))) = protocol::receive(&mut reader) {
+                if events.send(Event::Turned(id, direction)).is_err() {
+                    return;
+                }
+            }
+            let _ = events.send(Event::Left(id));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Position;
+    use crate::net::Client;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn serve(on_disconnect: OnDisconnect) -> Server {
+        let options = ServerOptions {
+            on_disconnect,
+            ..ServerOptions::default()
+        };
+        Server::bind("127.0.0.1:0", options).unwrap()
+    }
+
+    /// handles events until `done` is true, since clients talk to the server in the background
+    fn pump(server: &mut Server, done: impl Fn(&Server) -> bool) {
+        let started = Instant::now();
+        while !done(server) {
+            assert!(started.elapsed() < TIMEOUT, "the server never caught up");
+            server.handle_events();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn join(server: &mut Server) -> (Client, usize) {
+        let client = Client::connect(server.local_addr()).unwrap();
+        let connections = server.count_connections();
+        pump(server, |s| s.count_connections() > connections);
+        match client.receive_timeout(TIMEOUT).unwrap() {
+            ServerMessage::Welcome {
+                player: Some(player),
+                ..
+            } => (client, player),
+            message => panic!("expected a welcome, got {:?}", message),
+        }
+    }
+
+    fn next_delta(client: &Client) -> BoardDelta {
+        match client.receive_timeout(TIMEOUT).unwrap() {
+            ServerMessage::Tick(delta) => delta,
+            message => panic!("expected a tick, got {:?}", message),
+        }
+    }
+
+    #[test]
+    fn it_wont_serve_a_board_too_small_to_play_on() {
+        let options = ServerOptions {
+            width: 2,
+            height: 10,
+            ..ServerOptions::default()
+        };
+        let error = Server::bind("127.0.0.1:0", options).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn clients_cant_see_where_apples_will_grow() {
+        let mut server = serve(OnDisconnect::Die);
+        let client = Client::connect(server.local_addr()).unwrap();
+        pump(&mut server, |s| s.count_connections() > 0);
+        let state = match client.receive_timeout(TIMEOUT).unwrap() {
+            ServerMessage::Welcome { state, .. } => state,
+            message => panic!("expected a welcome, got {:?}", message),
+        };
+        let served = server.get_state().unwrap();
+        assert_eq!(state.get_players(), served.get_players());
+        assert_ne!(state.get_seed(), served.get_seed());
+        assert_ne!(state.get_hash(), served.get_hash());
+    }
+
+    #[test]
+    fn clients_steer_their_own_sneks() {
+        let mut server = serve(OnDisconnect::Die);
+        let (mut one, player_one) = join(&mut server);
+        let (two, player_two) = join(&mut server);
+        assert_eq!((player_one, player_two), (0, 1));
+        one.turn(Direction::Down).unwrap();
+        pump(&mut server, |s| {
+            s.get_state().unwrap().get_players()[0].get_direction() == Direction::Down
+        });
+        server.tick();
+        for client in [&one, &two] {
+            let delta = next_delta(client);
+            assert_eq!(delta.tick, 1);
+            assert_eq!(delta.players.len(), 2);
+            assert!(!delta.changes.is_empty());
+        }
+        let head = server
+            .get_state()
+            .unwrap()
+            .get_snek()
+            .get_head()
+            .get_position();
+        assert_eq!(head, Position::new(1, 2));
+    }
+
+    #[test]
+    fn deltas_keep_clients_in_step() {
+        let mut server = serve(OnDisconnect::Die);
+        let (client, _) = join(&mut server);
+        let mut board = Board::new(server.get_state().unwrap());
+        for _ in 0..5 {
+            server.tick();
+            board.apply(&next_delta(&client).changes);
+        }
+        assert_eq!(board, Board::new(server.get_state().unwrap()));
+    }
+
+    #[test]
+    fn sneks_die_when_their_player_disconnects() {
+        let mut server = serve(OnDisconnect::Die);
+        let (one, _) = join(&mut server);
+        let (two, _) = join(&mut server);
+        drop(two);
+        pump(&mut server, |s| s.count_connections() == 1);
+        server.tick();
+        let delta = next_delta(&one);
+        assert_eq!(delta.players[1].death_cause, Some(DeathCause::Disconnected));
+        assert!(delta.game_over);
+    }
+
+    #[test]
+    fn the_server_steers_sneks_whose_player_disconnected() {
+        let mut server = serve(OnDisconnect::Bot);
+        let (one, _) = join(&mut server);
+        let (two, _) = join(&mut server);
+        drop(two);
+        pump(&mut server, |s| s.count_connections() == 1);
+        for _ in 0..10 {
+            server.tick();
+            let delta = next_delta(&one);
+            assert!(delta.players[1].bot);
+            assert_eq!(delta.players[1].death_cause, None);
+        }
+    }
+
+    #[test]
+    fn a_new_round_starts_after_one_ends() {
+        let mut server = serve(OnDisconnect::Die);
+        let (mut client, _) = join(&mut server);
+        client.turn(Direction::Up).unwrap();
+        pump(&mut server, |s| {
+            s.get_state().unwrap().get_direction() == Direction::Up
+        });
+        server.tick();
+        assert!(next_delta(&client).game_over);
+        for _ in 0..=ROUND_BREAK_TICKS {
+            server.tick();
+        }
+        match client.receive_timeout(TIMEOUT).unwrap() {
+            ServerMessage::Welcome { player, state } => {
+                assert_eq!(player, Some(0));
+                assert_eq!(state.get_tick(), 0);
+            }
+            message => panic!("expected a welcome, got {:?}", message),
+        }
+    }
+
+    #[test]
+    fn the_game_stops_when_everyone_leaves() {
+        let mut server = serve(OnDisconnect::Die);
+        let (client, _) = join(&mut server);
+        drop(client);
+        pump(&mut server, |s| s.count_connections() == 0);
+        assert!(server.get_state().is_none());
+    }
+}
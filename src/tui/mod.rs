@@ -1,6 +1,9 @@
 mod theme;
 mod views;
 
+use crate::core::Bot;
+use crate::net::{Client, Peer};
+
 use cursive::{Cursive, CursiveExt};
 
 pub struct Tui {
@@ -10,12 +13,39 @@ pub struct Tui {
 impl Tui {
     pub fn new() -> Self {
         let mut app = Cursive::default();
-        app.add_global_callback('q', Cursive::quit);
+        app.add_global_callback('q', views::quit);
         theme::set(&mut app);
         views::title_screen(&mut app);
         Tui { app }
     }
 
+    /// skips the title screen and joins a game on a server
+    pub fn connect(client: Client) -> Self {
+        let mut app = Cursive::default();
+        app.add_global_callback('q', views::quit);
+        theme::set(&mut app);
+        views::connect(&mut app, client);
+        Tui { app }
+    }
+
+    /// skips the title screen and watches a bot play
+    pub fn watch(bot: Bot) -> Self {
+        let mut app = Cursive::default();
+        app.add_global_callback('q', views::quit);
+        theme::set(&mut app);
+        views::watch(&mut app, bot);
+        Tui { app }
+    }
+
+    /// skips the title screen and plays against another peer
+    pub fn play_peer(peer: Peer) -> Self {
+        let mut app = Cursive::default();
+        app.add_global_callback('q', views::quit);
+        theme::set(&mut app);
+        views::play_peer(&mut app, peer);
+        Tui { app }
+    }
+
     pub fn run(&mut self) {
         self.app.run();
     }
@@ -1,36 +1,136 @@
-use crate::core::{Apple, DeathCause, Direction, Position, Segment, SlitherResult, State};
+mod score;
 
-use crossbeam_channel::unbounded;
+use crate::core::{
+    data_dir, Achievement, Apple, AppleKind, Bot, Clock, Controller, DeathCause, Direction,
+    GameEvent, GameOptions, HighScore, Keyboard, Level, Position, PowerUpKind, Replay, Segment,
+    SlitherResult, State, Stats, StatsTracker, Topology,
+};
+use crate::net::{Board, Client, Peer, PlayerStatus, ServerMessage, Tile};
+use crate::tui::views::high_scores;
+use score::ScoreView;
+
+use crossbeam_channel::{unbounded, Sender};
 
 use cursive::{
-    event::{Event, EventResult},
+    event::{Event, EventResult, Key},
     view::View,
-    views::{Dialog, LinearLayout, NamedView, TextView, ViewRef},
+    views::{Dialog, LinearLayout, NamedView},
     Cursive, Printer, Vec2,
 };
 
 use rayon::prelude::*;
 
-pub fn new_game(app: &mut Cursive) {
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// how many times a second the board is drawn. the game ticks at its
+/// own pace, so this only needs to be often enough that a tick is never
+/// drawn late, and that a game on a server is never far behind.
+const FPS: u32 = 30;
+
+pub fn new_game(app: &mut Cursive, options: GameOptions) {
     tracing::debug!("new game");
-    let board_view = BoardView::new();
-    let score_view = TextView::new(board_view.get_score_content()).center();
+    start_game(app, BoardView::new(options));
+}
+
+pub fn play_level(app: &mut Cursive, level: Level) {
+    tracing::debug!("playing level {}", level.get_name());
+    start_game(app, BoardView::from_level(level));
+}
+
+pub fn continue_game(app: &mut Cursive) {
+    tracing::debug!("continuing saved game");
+    match autosave_path().and_then(State::load) {
+        Ok(state) => start_game(app, BoardView::from_state(state)),
+        Err(e) => {
+            tracing::info!("could not load saved game: {}", e);
+            app.add_layer(Dialog::info(format!("could not load ssaved game: {}", e)));
+        }
+    }
+}
+
+/// sits back and watches a bot play a classic game
+pub fn watch(app: &mut Cursive, bot: Bot) {
+    tracing::debug!("watching the {} bot play", bot.describe());
+    start_game(app, BoardView::watching(bot));
+}
+
+/// plays a game hosted with `snek serve`
+pub fn connect(app: &mut Cursive, client: Client) {
+    tracing::debug!("joining a game on a server");
+    match client.receive_timeout(Duration::from_secs(5)) {
+        Ok(ServerMessage::Welcome { player, state }) => {
+            start_game(app, BoardView::from_remote(client, player, *state));
+        }
+        Ok(message) => {
+            tracing::info!("expected a welcome from the server, got {:?}", message);
+            app.add_layer(Dialog::info("the ssserver didn't sssay hello"));
+        }
+        Err(e) => {
+            tracing::info!("could not join the server: {}", e);
+            app.add_layer(Dialog::info(format!("could not join the ssserver: {}", e)));
+        }
+    }
+}
+
+/// plays a game against another peer, without a server in between
+pub fn play_peer(app: &mut Cursive, peer: Peer) {
+    tracing::debug!("playing against another peer");
+    start_game(app, BoardView::from_peer(peer));
+}
+
+pub fn has_saved_game() -> bool {
+    autosave_path().map(|p| p.exists()).unwrap_or(false)
+}
+
+/// saves the game in progress, if there is one,
+/// so it can be continued from the title screen
+pub fn autosave(app: &mut Cursive) {
+    app.call_on_name("board", |board_view: &mut BoardView| board_view.autosave());
+}
+
+fn autosave_path() -> std::io::Result<PathBuf> {
+    Ok(data_dir()?.join("autosave.json"))
+}
+
+fn start_game(app: &mut Cursive, mut board_view: BoardView) {
+    let score_view = board_view.score_view();
+    board_view.track_stats();
     let named_board_view = NamedView::new("board", board_view);
-    let named_score_view = NamedView::new("score", score_view);
     app.pop_layer();
-    // let wrapped_view; // replae linear layout with wrapped view
-    // implement required_size on the score view
     app.add_fullscreen_layer(
         LinearLayout::vertical()
-            .child(named_score_view)
+            .child(score_view)
             .child(named_board_view),
     );
     app.focus_name("board").unwrap();
-    app.set_fps(6);
+    app.set_fps(FPS);
 }
 
 struct BoardView {
     has_resized: bool,
+    /// whether the board keeps its size instead of filling the screen
+    fixed_size: bool,
+    game_over: bool,
+    level: Option<Level>,
+    /// the bot playing, if we're only watching
+    bot: Option<Bot>,
+    remote: Option<Remote>,
+    peer: Option<Peer>,
+    /// what steers each snek in a game played here
+    controllers: Vec<Box<dyn Controller>>,
+    /// where each player's key presses go, if a keyboard steers their snek
+    keys: Vec<Option<Sender<Direction>>>,
+    /// when it's time for the next tick, since the board's drawn more often than that
+    clock: Clock,
+    /// everyone listening to the game here, who'll want to hear
+    /// about the new one if the board's resized before it starts
+    subscribers: Vec<Sender<GameEvent>>,
+    /// counts the game towards the lifetime stats, if it's one of ours
+    tracker: Option<StatsTracker>,
+    stats: Stats,
     pub size: Vec2,
     pub cells: Vec<Cell>,
     state: State,
@@ -38,21 +138,271 @@ struct BoardView {
 }
 
 impl BoardView {
-    pub fn new() -> Self {
+    pub fn new(options: GameOptions) -> Self {
         let min_width = 6;
         let min_height = 6;
         BoardView {
             size: Vec2::new(min_width, min_height),
             cells: vec![Cell::Free; min_width * min_height],
-            state: State::new(min_width, min_height),
+            fixed_size: false,
+            ..Self::from_state(Self::new_state(min_width, min_height, options))
+        }
+    }
+
+    pub fn from_state(state: State) -> Self {
+        let (width, height) = state.get_walls().get_dimensions();
+        let (keys, controllers) = state
+            .get_players()
+            .iter()
+            .map(|_| {
+                let (keys, keyboard) = Keyboard::new();
+                let controller: Box<dyn Controller> = Box::new(keyboard);
+                (Some(keys), controller)
+            })
+            .unzip();
+        BoardView {
+            size: Vec2::new(width, height),
+            cells: vec![Cell::Free; width * height],
+            state,
             has_resized: false,
+            fixed_size: true,
+            game_over: false,
+            level: None,
+            bot: None,
+            remote: None,
+            peer: None,
+            controllers,
+            keys,
+            clock: Clock::new(),
+            subscribers: Vec::new(),
+            tracker: None,
+            stats: Stats::new(),
             offset: 2,
         }
     }
 
-    fn turn_snek(&mut self, direction: Direction) -> EventResult {
-        if self.state.turn_snek(direction) {
-            let mut wormy_head = *self.state.get_snek().get_head();
+    pub fn from_level(level: Level) -> Self {
+        let mut state = level.to_state(rand::random());
+        state.start_recording();
+        BoardView {
+            level: Some(level),
+            ..Self::from_state(state)
+        }
+    }
+
+    /// a game the bot plays on its own, with the keys doing nothing
+    fn watching(bot: Bot) -> Self {
+        let board_view = Self::new(GameOptions::default());
+        // every bot can find its way round the smallest board
+        let controller = bot.to_controller(board_view.state.get_walls()).unwrap();
+        BoardView {
+            bot: Some(bot),
+            controllers: vec![controller],
+            keys: vec![None],
+            ..board_view
+        }
+    }
+
+    /// a game played on a server, where `state` is how the game
+    /// started and everything since arrives as board deltas
+    fn from_remote(client: Client, player: Option<usize>, state: State) -> Self {
+        let statuses = Self::get_statuses(&state);
+        BoardView {
+            remote: Some(Remote {
+                client,
+                player,
+                statuses,
+                game_over: false,
+                scores: None,
+            }),
+            ..Self::from_state(state)
+        }
+    }
+
+    /// a game against another peer, where `state` is only ever a copy of the peer's
+    fn from_peer(peer: Peer) -> Self {
+        let state = peer.get_state().clone();
+        BoardView {
+            peer: Some(peer),
+            ..Self::from_state(state)
+        }
+    }
+
+    /// a score bar that keeps up with this game
+    fn score_view(&mut self) -> ScoreView {
+        if let Some(remote) = &mut self.remote {
+            let (scores, receiver) = unbounded();
+            remote.scores = Some(scores);
+            remote.update_score_bar();
+            return ScoreView::server(receiver);
+        }
+        let events = match &mut self.peer {
+            Some(peer) => peer.subscribe(),
+            None => {
+                let (subscriber, events) = unbounded();
+                self.state.add_subscriber(subscriber.clone());
+                self.subscribers.push(subscriber);
+                events
+            }
+        };
+        let you = self.peer.as_ref().map(|p| p.get_player());
+        ScoreView::new(&self.state, events, self.level.clone(), self.bot, you)
+    }
+
+    /// starts counting the game towards the lifetime stats. games on a server
+    /// or against a peer aren't counted, since a peer can rewind the game
+    /// and a server only sends the board, and nor are the bot's.
+    fn track_stats(&mut self) {
+        if self.is_networked() || self.bot.is_some() {
+            return;
+        }
+        match Stats::default_path().and_then(Stats::load) {
+            Ok(stats) => self.stats = stats,
+            Err(e) => {
+                tracing::info!("could not load stats: {}", e);
+                return;
+            }
+        }
+        let (subscriber, events) = unbounded();
+        self.state.add_subscriber(subscriber.clone());
+        self.subscribers.push(subscriber);
+        self.tracker = Some(StatsTracker::new(events, &self.state, 0));
+    }
+
+    /// counts whatever's happened since the last tick towards the lifetime
+    /// stats, saving them if the game's over, and returns any achievements
+    /// that were unlocked
+    fn update_stats(&mut self) -> Vec<Achievement> {
+        let unlocked = match &mut self.tracker {
+            Some(tracker) => tracker.update(&mut self.stats, &self.state),
+            None => return Vec::new(),
+        };
+        if self.game_over {
+            self.save_stats();
+        }
+        unlocked
+    }
+
+    fn save_stats(&self) {
+        if self.tracker.is_none() {
+            return;
+        }
+        if let Err(e) = Stats::default_path().and_then(|path| self.stats.save(path)) {
+            tracing::info!("could not save stats: {}", e);
+        }
+    }
+
+    /// shows a layer for every achievement that was just unlocked. the
+    /// game waits underneath until they've been seen.
+    fn announce(&mut self, unlocked: Vec<Achievement>) -> EventResult {
+        if unlocked.is_empty() {
+            return EventResult::Consumed(None);
+        }
+        self.clock.stop();
+        EventResult::with_cb(move |s| {
+            for achievement in &unlocked {
+                s.add_layer(
+                    Dialog::text(format!(
+                        "{}\n\n{}",
+                        achievement.get_name(),
+                        achievement.describe()
+                    ))
+                    .title("achievement unlocked!")
+                    .button("nice", |s| {
+                        s.pop_layer();
+                    }),
+                );
+            }
+        })
+    }
+
+    /// whether the game is really being played somewhere else
+    fn is_networked(&self) -> bool {
+        self.remote.is_some() || self.peer.is_some()
+    }
+
+    fn get_statuses(state: &State) -> Vec<PlayerStatus> {
+        state
+            .get_players()
+            .iter()
+            .map(|p| PlayerStatus {
+                score: p.get_score(),
+                death_cause: p.get_death_cause(),
+                bot: false,
+            })
+            .collect()
+    }
+
+    /// starts another game just like this one
+    fn play_again(&self) -> impl Fn(&mut Cursive) + Clone {
+        let options = self.state.get_options();
+        let level = self.level.clone();
+        let bot = self.bot;
+        move |s| match (&level, bot) {
+            (Some(level), _) => play_level(s, level.clone()),
+            (None, Some(bot)) => watch(s, bot),
+            (None, None) => new_game(s, options),
+        }
+    }
+
+    fn autosave(&self) {
+        self.save_stats();
+        // games on a server aren't ours to save, and nor are the bot's
+        if self.game_over || self.is_networked() || self.bot.is_some() {
+            return;
+        }
+        if let Err(e) = autosave_path().and_then(|path| self.state.save(path)) {
+            tracing::info!("could not autosave: {}", e);
+        }
+    }
+
+    /// a finished game can't be continued, so throw away its autosave
+    fn end_game(&mut self) {
+        self.game_over = true;
+        if let Ok(path) = autosave_path() {
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(path) {
+                    tracing::info!("could not remove autosave: {}", e);
+                }
+            }
+        }
+    }
+
+    fn new_state(width: usize, height: usize, options: GameOptions) -> State {
+        let mut state = State::with_options(width, height, rand::random(), options);
+        state.start_recording();
+        state
+    }
+
+    fn turn_snek(&mut self, player: usize, direction: Direction) -> EventResult {
+        if let Some(remote) = &mut self.remote {
+            // whichever keys are pressed, they steer our own snek
+            if remote.player.is_some() {
+                if let Err(e) = remote.client.turn(direction) {
+                    tracing::info!("could not tell the server to turn: {}", e);
+                }
+            }
+            return EventResult::Consumed(None);
+        }
+        if let Some(peer) = &mut self.peer {
+            peer.turn(direction);
+            return EventResult::Consumed(None);
+        }
+        let keys = match self.keys.get(player) {
+            Some(Some(keys)) => keys,
+            _ => return EventResult::Ignored,
+        };
+        let snek = self.state.get_players()[player].get_snek();
+        let mut wormy_head = *snek.get_head();
+        let is_next = keys.is_empty();
+        // presses beyond what the keyboard has room for are dropped
+        if keys.try_send(direction).is_ok()
+            && is_next
+            && !self.state.is_game_over()
+            && !direction.is_on_a_dime(&wormy_head.get_direction())
+        {
+            // the keyboard only turns the snek on the next tick,
+            // but show which way it's about to go
             wormy_head.set_direction(&direction);
             self.draw_segment(&wormy_head);
         }
@@ -60,15 +410,29 @@ impl BoardView {
     }
 
     fn pause(&mut self) -> EventResult {
-        EventResult::with_cb(|s| {
-            let old_fps = s.fps();
-            let controls_dialog = Dialog::text(
-                "  ~~~ controlsss ~~~
+        if self.is_networked() {
+            // the server keeps on ticking whatever we do
+            return EventResult::Ignored;
+        }
+        self.autosave();
+        self.clock.stop();
+        let controls = if self.state.get_players().len() > 1 {
+            "  ~~~ controlsss ~~~
+
+ player one ~~~> wasssd
+ player two ~~~> arrowsss
+    paussse ~~~> p
+     ssstop ~~~> q"
+        } else {
+            "  ~~~ controlsss ~~~
 
 ssslither ~~~> wasssd
   paussse ~~~> p
-   ssstop ~~~> q",
-            );
+   ssstop ~~~> q"
+        };
+        EventResult::with_cb(move |s| {
+            let old_fps = s.fps();
+            let controls_dialog = Dialog::text(controls);
             s.set_fps(0);
             s.add_layer(NamedView::new(
                 "pause",
@@ -82,9 +446,41 @@ ssslither ~~~> wasssd
         })
     }
 
+    /// where every apple and power-up is right now
+    fn get_item_positions(&self) -> Vec<Position> {
+        self.state
+            .get_apples()
+            .iter()
+            .map(|a| a.get_position())
+            .chain(self.state.get_power_ups().iter().map(|p| p.get_position()))
+            .collect()
+    }
+
+    /// runs every tick that's come due since the board was last drawn
+    fn refresh(&mut self) -> EventResult {
+        if self.remote.is_some() {
+            // the server keeps time for us
+            return self.tick_remote();
+        }
+        self.clock.frame(Instant::now());
+        let mut event_result = EventResult::Consumed(None);
+        let mut unlocked = Vec::new();
+        while !self.game_over && self.clock.take_tick(self.state.get_tick_duration()) {
+            event_result = event_result.and(self.tick());
+            unlocked.extend(self.update_stats());
+        }
+        event_result.and(self.announce(unlocked))
+    }
+
     fn tick(&mut self) -> EventResult {
-        let old_apple = self.state.get_apple().get_position();
-        let slither_result = self.state.tick();
+        if self.peer.is_some() {
+            return self.tick_peer();
+        }
+        if self.state.get_players().len() > 1 {
+            return self.tick_versus();
+        }
+        let old_items = self.get_item_positions();
+        let slither_result = self.state.tick_with(&mut self.controllers).swap_remove(0);
         match slither_result {
             SlitherResult::Died(death_cause) => {
                 self.state
@@ -94,27 +490,50 @@ ssslither ~~~> wasssd
                     .for_each(|s| self.draw_segment(s));
                 self.die_alog(death_cause)
             }
-            SlitherResult::AteTheWorld => EventResult::with_cb(|s| {
-                s.set_autorefresh(false);
-                s.add_layer(Dialog::text("snek ate the world!").button("Ok", |s| {
-                    s.pop_layer();
-                    s.pop_layer();
-                    new_game(s);
-                }));
-            }),
+            SlitherResult::AteTheWorld if self.bot.is_some() => {
+                self.end_game();
+                self.end_dialog("snek ate the world!".to_string())
+            }
+            SlitherResult::AteTheWorld => {
+                self.end_game();
+                let high_score = HighScore::new("", &self.state, None);
+                let play_again = self.play_again();
+                let text = format!("snek ate the world!{}", self.describe_score());
+                EventResult::with_cb(move |s| {
+                    s.set_autorefresh(false);
+                    let play_again = play_again.clone();
+                    s.add_layer(Dialog::text(&text).button("Ok", move |s| {
+                        s.pop_layer();
+                        s.pop_layer();
+                        play_again(s);
+                    }));
+                    high_scores::prompt_for_name(s, high_score.clone());
+                })
+            }
             SlitherResult::Grew {
                 direction: _,
                 segments,
                 slime_trail,
+                eaten: _,
             } => {
                 self.free_cell(slime_trail);
-                self.update_apple(Some(old_apple));
+                self.update_items(&old_items);
                 segments.iter().for_each(|s| self.draw_segment(s));
-                let score_content = self.get_score_content();
-                EventResult::with_cb(move |s| {
-                    let mut score_view: ViewRef<TextView> = s.find_name("score").unwrap();
-                    score_view.set_content(&score_content);
-                })
+                EventResult::Consumed(None)
+            }
+            SlitherResult::Shrank {
+                direction: _,
+                segments,
+                slime_trail,
+            } => {
+                slime_trail.into_iter().for_each(|p| self.free_cell(p));
+                self.update_items(&old_items);
+                segments.iter().for_each(|s| self.draw_segment(s));
+                EventResult::Consumed(None)
+            }
+            SlitherResult::Shielded { direction: _ } => {
+                self.update_items(&old_items);
+                EventResult::Consumed(None)
             }
             SlitherResult::Slithered {
                 direction: _,
@@ -122,58 +541,306 @@ ssslither ~~~> wasssd
                 slime_trail,
             } => {
                 self.free_cell(slime_trail);
-                self.update_apple(Some(old_apple));
+                self.update_items(&old_items);
                 segments.iter().for_each(|s| self.draw_segment(s));
                 EventResult::Consumed(None)
             }
         }
     }
 
+    /// ticks a game against each other. sneks that die are taken
+    /// off the board, since the others can slither over them.
+    fn tick_versus(&mut self) -> EventResult {
+        let old_items = self.get_item_positions();
+        let old_sneks: Vec<Vec<Position>> = self
+            .state
+            .get_players()
+            .iter()
+            .map(|p| p.get_snek().get_segment_positions())
+            .collect();
+        let slither_results = self.state.tick_with(&mut self.controllers);
+        for (player, slither_result) in slither_results.into_iter().enumerate() {
+            match slither_result {
+                SlitherResult::Died(_) => old_sneks[player].iter().for_each(|p| self.free_cell(*p)),
+                SlitherResult::Grew { slime_trail, .. }
+                | SlitherResult::Slithered { slime_trail, .. } => self.free_cell(slime_trail),
+                SlitherResult::Shrank { slime_trail, .. } => {
+                    slime_trail.into_iter().for_each(|p| self.free_cell(p))
+                }
+                SlitherResult::Shielded { .. } | SlitherResult::AteTheWorld => {}
+            }
+        }
+        self.update_items(&old_items);
+        self.draw_sneks();
+        if self.state.is_game_over() {
+            self.versus_alog()
+        } else {
+            EventResult::Consumed(None)
+        }
+    }
+
+    /// catches up with everything the server said since the last refresh
+    fn tick_remote(&mut self) -> EventResult {
+        loop {
+            let remote = self.remote.as_mut().unwrap();
+            match remote.client.try_receive() {
+                Ok(Some(ServerMessage::Welcome { player, state })) => {
+                    remote.player = player;
+                    remote.statuses = Self::get_statuses(&state);
+                    remote.game_over = false;
+                    remote.update_score_bar();
+                    let (width, height) = state.get_walls().get_dimensions();
+                    self.state = *state;
+                    self.size = Vec2::new(width, height);
+                    self.cells = vec![Cell::Free; width * height];
+                    self.redraw();
+                }
+                Ok(Some(ServerMessage::Tick(delta))) => {
+                    remote.statuses = delta.players;
+                    remote.game_over = delta.game_over;
+                    remote.update_score_bar();
+                    for (position, tile) in delta.changes {
+                        self.update_cell(position, Cell::from(tile));
+                    }
+                }
+                Ok(None) => return EventResult::Consumed(None),
+                Err(e) => {
+                    tracing::info!("lost the server: {}", e);
+                    self.game_over = true;
+                    return EventResult::with_cb(|s| {
+                        s.set_autorefresh(false);
+                        s.set_fps(0);
+                        s.add_layer(
+                            Dialog::text("lossst the ssserver").button("quit", |s| s.quit()),
+                        );
+                    });
+                }
+            }
+        }
+    }
+
+    /// ticks the peer, which might have rewound the game, so the whole board is drawn again
+    fn tick_peer(&mut self) -> EventResult {
+        let peer = self.peer.as_mut().unwrap();
+        if let Err(desync) = peer.tick() {
+            tracing::info!("lost sync with the other peer: {}", desync);
+            self.game_over = true;
+            return EventResult::with_cb(move |s| {
+                s.set_autorefresh(false);
+                s.set_fps(0);
+                s.add_layer(
+                    Dialog::text(format!("out of sssync: {}", desync)).button("quit", |s| s.quit()),
+                );
+            });
+        }
+        let game_over = peer.is_game_over();
+        self.state.clone_from(peer.get_state());
+        self.cells.iter_mut().for_each(|c| *c = Cell::Free);
+        self.redraw();
+        if game_over {
+            self.versus_alog()
+        } else {
+            EventResult::Consumed(None)
+        }
+    }
+
     fn resize(&mut self, constraints: Vec2) {
         if !self.has_resized {
+            if self.fixed_size {
+                // restored games and levels keep the board size they came with
+                self.has_resized = true;
+                self.redraw();
+                return;
+            }
+            let mut constraints = constraints;
             if constraints > self.size {
+                self.state =
+                    Self::new_state(constraints.x, constraints.y, self.state.get_options());
+                if let Some(bot) = self.bot {
+                    match bot.to_controller(self.state.get_walls()) {
+                        Ok(controller) => self.controllers = vec![controller],
+                        Err(e) => {
+                            // one column fewer and there's an even number of them
+                            tracing::info!("{}, so the board is one column narrower", e);
+                            constraints.x -= 1;
+                            self.state = Self::new_state(
+                                constraints.x,
+                                constraints.y,
+                                self.state.get_options(),
+                            );
+                            let controller = bot.to_controller(self.state.get_walls()).unwrap();
+                            self.controllers = vec![controller];
+                        }
+                    }
+                }
+                for subscriber in &self.subscribers {
+                    self.state.add_subscriber(subscriber.clone());
+                }
                 self.size = constraints;
-                self.state = State::new(constraints.x, constraints.y);
                 self.cells = vec![Cell::Free; constraints.x * constraints.y];
             }
-            self.update_walls();
-            assert_eq!(
-                self.cells.last().unwrap(),
-                &Cell::Wall(WallType::BottomRightCorner)
-            );
-            self.update_apple(None);
-            self.state
+            self.redraw();
+            if let Topology::Bounded = self.state.get_walls().get_topology() {
+                assert!(matches!(
+                    self.cells.last(),
+                    Some(Cell::Wall(WallType {
+                        up: true,
+                        left: true,
+                        down: false,
+                        right: false,
+                    }))
+                ));
+            }
+            self.has_resized = true;
+            self.size = constraints;
+        }
+    }
+
+    fn redraw(&mut self) {
+        self.update_walls();
+        if self.is_networked() {
+            // walls are never on the board, so leave them be
+            for (position, tile) in Board::new(&self.state).get_tiles() {
+                if tile != Tile::Free {
+                    self.update_cell(position, Cell::from(tile));
+                }
+            }
+            return;
+        }
+        self.update_items(&[]);
+        self.draw_sneks();
+    }
+
+    /// draws every snek that's still alive, or the only snek
+    /// in a game on your own so its skull shows where it died
+    fn draw_sneks(&mut self) {
+        let players = self.state.get_players().to_owned();
+        let solo = players.len() == 1;
+        for player in players.iter().filter(|p| solo || p.is_alive()) {
+            player
                 .get_snek()
                 .get_segments()
                 .iter()
                 .for_each(|s| self.draw_segment(s));
-            self.has_resized = true;
-            self.size = constraints;
         }
     }
 
+    /// says who won a game against each other
+    fn versus_alog(&mut self) -> EventResult {
+        self.end_game();
+        let mut text = match self.state.get_winner() {
+            Some(winner) => format!("player {} winsss!", describe_player(winner)),
+            None => "nobody winsss".to_string(),
+        };
+        for (i, player) in self.state.get_players().iter().enumerate() {
+            if let Some(death_cause) = player.get_death_cause() {
+                text.push_str(&format!(
+                    "\n\nplayer {}'s snek died because {}",
+                    describe_player(i),
+                    death_cause.describe()
+                ));
+            }
+        }
+        if let Some(replay_file) = self.save_replay() {
+            text.push_str(&format!("\n\nreplay ssaved to {}", replay_file));
+        }
+        self.end_dialog(text)
+    }
+
+    /// shows how the game ended, with a way to play again
+    fn end_dialog(&self, text: String) -> EventResult {
+        if self.peer.is_some() {
+            // the other peer would have to start again too
+            return EventResult::with_cb(move |s| {
+                s.add_layer(Dialog::text(&text).button("quit", |s| s.quit()));
+                s.set_autorefresh(false);
+            });
+        }
+        let play_again = self.play_again();
+        EventResult::with_cb(move |s| {
+            let play_again = play_again.clone();
+            s.add_layer(
+                Dialog::text(&text)
+                    .button("play again", move |s| {
+                        s.pop_layer();
+                        s.pop_layer();
+                        play_again(s);
+                    })
+                    .button("quit", |s| s.quit()),
+            );
+            s.set_autorefresh(false);
+        })
+    }
+
     fn die_alog(&mut self, death_cause: DeathCause) -> EventResult {
-        let text = death_cause.describe().to_string();
+        self.end_game();
+        let mut text = death_cause.describe().to_string();
+        text.push_str(&self.describe_score());
+        if let Some(replay_file) = self.save_replay() {
+            text.push_str(&format!("\n\nreplay ssaved to {}", replay_file));
+        }
+        if self.state.get_players().len() > 1 || self.bot.is_some() {
+            // there's no high score table for games against each other, or for bots
+            return self.end_dialog(text);
+        }
+        let high_score = HighScore::new("", &self.state, Some(death_cause));
+        let play_again = self.play_again();
         EventResult::with_cb(move |s| {
+            let play_again = play_again.clone();
             s.add_layer(
                 Dialog::text(&text)
-                    .button("play again", |s| {
+                    .button("play again", move |s| {
                         s.pop_layer();
                         s.pop_layer();
-                        new_game(s);
+                        play_again(s);
                     })
                     .button("quit", |s| s.quit()),
             );
+            high_scores::prompt_for_name(s, high_score.clone());
             s.set_autorefresh(false);
         })
     }
 
-    fn update_apple(&mut self, old_apple: Option<Position>) {
-        let apple = self.state.get_apple().to_owned();
-        if let Some(old_apple) = old_apple {
-            self.update_cell(old_apple, Cell::Free);
+    /// where a game on your own's points came from, if
+    /// there was more to it than the apples
+    fn describe_score(&self) -> String {
+        let breakdown = self.state.get_players()[0]
+            .get_scoreboard()
+            .get_breakdown()
+            .describe();
+        if self.state.get_players().len() > 1 || breakdown.is_empty() {
+            return String::new();
+        }
+        format!(
+            "\n\n{}\nssscore: {}",
+            breakdown.join("\n"),
+            self.state.get_score()
+        )
+    }
+
+    fn save_replay(&self) -> Option<String> {
+        let replay = self.state.get_replay()?;
+        match Replay::default_path().and_then(|path| replay.save(&path).map(|()| path)) {
+            Ok(path) => Some(path.display().to_string()),
+            Err(e) => {
+                tracing::info!("could not save replay: {}", e);
+                None
+            }
+        }
+    }
+
+    /// clears where the apples and power-ups used to be, in case they
+    /// were eaten, picked up or rotted away, then draws every one on the board
+    fn update_items(&mut self, old_items: &[Position]) {
+        for old_item in old_items {
+            self.free_cell(*old_item);
+        }
+        for apple in self.state.get_apples().to_owned() {
+            self.update_cell(apple.get_position(), Cell::Apple(apple));
+        }
+        for power_up in self.state.get_power_ups().to_owned() {
+            self.update_cell(power_up.get_position(), Cell::PowerUp(power_up.get_kind()));
         }
-        self.update_cell(apple.get_position(), Cell::Apple(apple));
     }
 
     fn draw_segment(&mut self, segment: &Segment) {
@@ -193,27 +860,21 @@ ssslither ~~~> wasssd
 
     fn update_walls(&mut self) {
         let walls = self.state.get_walls().clone();
-        let left_wall = walls.left_wall();
-        let right_wall = walls.right_wall();
-        let top_wall = walls.top_wall();
-        let bottom_wall = walls.bottom_wall();
+        let (width, height) = walls.get_dimensions();
+        // walls only join up with walls that are actually on the board,
+        // even if the board wraps around
+        let connects = |position: &Position, direction: Direction| {
+            position.neighbor(direction).is_some_and(|neighbor| {
+                let (x, y) = neighbor.get_coordinates();
+                x < width && y < height && walls.is_wall(&neighbor)
+            })
+        };
         for position in walls.get_positions() {
-            let (x, y) = position.get_coordinates();
-            let wall_type = match (
-                (x == left_wall, x == right_wall),
-                (y == top_wall, y == bottom_wall),
-            ) {
-                ((true, false), (true, false)) => WallType::TopLeftCorner,
-                ((false, true), (true, false)) => WallType::TopRightCorner,
-                ((true, false), (false, true)) => WallType::BottomLeftCorner,
-                ((false, true), (false, true)) => WallType::BottomRightCorner,
-                ((true, false), (false, false)) => WallType::LeftWall,
-                ((false, true), (false, false)) => WallType::RightWall,
-                ((false, false), (true, false)) => WallType::TopWall,
-                ((false, false), (false, true)) => WallType::BottomWall,
-                _ => {
-                    unreachable!("{:?} is a bad wall segment", position)
-                }
+            let wall_type = WallType {
+                up: connects(&position, Direction::Up),
+                down: connects(&position, Direction::Down),
+                left: connects(&position, Direction::Left),
+                right: connects(&position, Direction::Right),
             };
             self.update_cell(position, Cell::Wall(wall_type));
         }
@@ -230,12 +891,18 @@ ssslither ~~~> wasssd
         Position::new(x, y)
     }
 
-    fn get_score_content(&self) -> String {
-        format!("ssscore: {}", self.state.get_score())
-    }
-
     fn user_resized(&mut self) -> EventResult {
-        self.die_alog(DeathCause::Resized)
+        if self.is_networked() {
+            // the board is the server's size, so there's nothing to redo
+            return EventResult::Consumed(None);
+        }
+        let event_result = self.die_alog(DeathCause::Resized);
+        // after the dialog's had a chance to save the replay, which stops once a snek is killed
+        for player in 0..self.state.get_players().len() {
+            self.state.kill_player(player, DeathCause::Resized);
+        }
+        let unlocked = self.update_stats();
+        event_result.and(self.announce(unlocked))
     }
 }
 
@@ -277,52 +944,151 @@ impl View for BoardView {
 
     fn on_event(&mut self, event: Event) -> EventResult {
         match event {
-            Event::Char('w') => self.turn_snek(Direction::Up),
-            Event::Char('a') => self.turn_snek(Direction::Left),
-            Event::Char('s') => self.turn_snek(Direction::Down),
-            Event::Char('d') => self.turn_snek(Direction::Right),
+            Event::Char('w') => self.turn_snek(0, Direction::Up),
+            Event::Char('a') => self.turn_snek(0, Direction::Left),
+            Event::Char('s') => self.turn_snek(0, Direction::Down),
+            Event::Char('d') => self.turn_snek(0, Direction::Right),
+            Event::Key(Key::Up) => self.turn_snek(1, Direction::Up),
+            Event::Key(Key::Left) => self.turn_snek(1, Direction::Left),
+            Event::Key(Key::Down) => self.turn_snek(1, Direction::Down),
+            Event::Key(Key::Right) => self.turn_snek(1, Direction::Right),
             Event::Char('p') | Event::FocusLost => self.pause(),
-            Event::Refresh => self.tick(),
+            Event::Refresh => self.refresh(),
             Event::WindowResize => self.user_resized(),
             _ => EventResult::Ignored,
         }
     }
 }
 
+/// a game being played on a server somewhere else
+struct Remote {
+    client: Client,
+    /// which snek is ours, or `None` if we're only watching
+    player: Option<usize>,
+    statuses: Vec<PlayerStatus>,
+    game_over: bool,
+    /// where to say what the score bar should show, once there is one
+    scores: Option<Sender<String>>,
+}
+
+impl Remote {
+    fn update_score_bar(&self) {
+        if let Some(scores) = &self.scores {
+            // the score bar going away just means nobody's looking
+            let _ = scores.send(self.get_score_content());
+        }
+    }
+
+    fn get_score_content(&self) -> String {
+        let mut scores: Vec<String> = self
+            .statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| {
+                let mut score = format!("player {}: {}", describe_player(i), status.score);
+                if self.player == Some(i) {
+                    score.push_str(" (you)");
+                }
+                if status.death_cause.is_some() {
+                    score.push_str(" 💀");
+                } else if status.bot {
+                    score.push_str(" (bot)");
+                }
+                score
+            })
+            .collect();
+        if self.player.is_none() {
+            scores.push("watching".to_string());
+        }
+        if self.game_over {
+            scores.push("round over".to_string());
+        }
+        scores.join("      ")
+    }
+}
+
+fn describe_player(player: usize) -> String {
+    match player {
+        0 => "one".to_string(),
+        1 => "two".to_string(),
+        2 => "three".to_string(),
+        3 => "four".to_string(),
+        _ => (player + 1).to_string(),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Cell {
     Snek(Segment),
     Apple(Apple),
+    PowerUp(PowerUpKind),
     Wall(WallType),
     Free,
 }
 
+/// which neighboring cells are also walls, so walls
+/// of any shape can be drawn joined up to each other
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum WallType {
-    TopWall,
-    BottomWall,
-    LeftWall,
-    RightWall,
-    TopLeftCorner,
-    BottomLeftCorner,
-    TopRightCorner,
-    BottomRightCorner,
+struct WallType {
+    up: bool,
+    down: bool,
+    left: bool,
+    right: bool,
+}
+
+impl WallType {
+    fn display(&self) -> &str {
+        match (self.up, self.down, self.left, self.right) {
+            (false, false, false, false) => "▪",
+            (false, false, false, true) => "╶─",
+            (false, false, true, false) => "╴",
+            (false, false, true, true) => "──",
+            (true, false, false, false) => "╵",
+            (false, true, false, false) => "╷",
+            (true, true, false, false) => "│",
+            (false, true, false, true) => "╭─",
+            (false, true, true, false) => "╮",
+            (true, false, false, true) => "╰─",
+            (true, false, true, false) => "╯",
+            (true, true, false, true) => "├─",
+            (true, true, true, false) => "┤",
+            (false, true, true, true) => "┬─",
+            (true, false, true, true) => "┴─",
+            (true, true, true, true) => "┼─",
+        }
+    }
+}
+
+impl From<Tile> for Cell {
+    fn from(tile: Tile) -> Self {
+        match tile {
+            Tile::Free => Cell::Free,
+            Tile::Snek(segment) => Cell::Snek(segment),
+            Tile::Apple(apple) => Cell::Apple(apple),
+            Tile::PowerUp(kind) => Cell::PowerUp(kind),
+        }
+    }
 }
 
 impl Cell {
     fn display(&self) -> String {
         match self {
             Cell::Snek(segment) => segment.display().to_string(),
-            Cell::Apple(_) => "🍎".to_string(),
-            Cell::Wall(wall_type) => match wall_type {
-                WallType::TopLeftCorner => "╭─",
-                WallType::BottomLeftCorner => "╰─",
-                WallType::BottomRightCorner => "╯",
-                WallType::TopRightCorner => "╮",
-                WallType::TopWall | WallType::BottomWall => "──",
-                WallType::LeftWall | WallType::RightWall => "│",
+            Cell::Apple(apple) => match apple.get_kind() {
+                AppleKind::Plain => "🍎",
+                AppleKind::Golden => "🌟",
+                AppleKind::Poison => "🍄",
+                AppleKind::Timed => "⏳",
+            }
+            .to_string(),
+            Cell::PowerUp(kind) => match kind {
+                PowerUpKind::Ghost => "👻",
+                PowerUpKind::SlowMotion => "🐌",
+                PowerUpKind::SpeedBurst => "⚡",
+                PowerUpKind::Shield => "🔰",
             }
             .to_string(),
+            Cell::Wall(wall_type) => wall_type.display().to_string(),
             Cell::Free => "  ".to_string(),
         }
     }
@@ -0,0 +1,165 @@
+use crate::core::{ActivePowerUp, Bot, GameEvent, Level, State};
+
+use crossbeam_channel::Receiver;
+
+use cursive::{
+    view::{View, ViewWrapper},
+    views::TextView,
+    wrap_impl, Vec2,
+};
+
+use super::describe_player;
+
+/// the bar along the top of the board. it listens to the game itself and
+/// keeps its own tally, so the board only has to worry about the board.
+pub struct ScoreView {
+    text: TextView,
+    source: Source,
+}
+
+enum Source {
+    /// a game being played here, or against a peer
+    Game(Game),
+    /// a game on a server, which sends what the bar should say
+    Server(Receiver<String>),
+}
+
+struct Game {
+    events: Receiver<GameEvent>,
+    players: Vec<Tally>,
+    level: Option<Level>,
+    /// the bot playing, if we're only watching
+    bot: Option<Bot>,
+    /// which snek is ours, when someone else is playing somewhere else
+    you: Option<usize>,
+}
+
+/// what's shown for one player
+#[derive(Debug, Clone)]
+struct Tally {
+    score: usize,
+    power_ups: Vec<ActivePowerUp>,
+}
+
+impl ScoreView {
+    /// a score bar for `state`, which will hear about the game from `events`
+    pub fn new(
+        state: &State,
+        events: Receiver<GameEvent>,
+        level: Option<Level>,
+        bot: Option<Bot>,
+        you: Option<usize>,
+    ) -> Self {
+        let players = state
+            .get_players()
+            .iter()
+            .map(|p| Tally {
+                score: p.get_score(),
+                power_ups: p.get_active_power_ups().to_vec(),
+            })
+            .collect();
+        let game = Game {
+            events,
+            players,
+            level,
+            bot,
+            you,
+        };
+        ScoreView {
+            text: TextView::new(game.get_content()).center(),
+            source: Source::Game(game),
+        }
+    }
+
+    /// a score bar for a game on a server, saying whatever comes through `scores`
+    pub fn server(scores: Receiver<String>) -> Self {
+        ScoreView {
+            text: TextView::new("").center(),
+            source: Source::Server(scores),
+        }
+    }
+
+    /// takes in everything that's happened since the bar was last drawn
+    fn catch_up(&mut self) {
+        match &mut self.source {
+            Source::Game(game) => {
+                if game.catch_up() {
+                    self.text.set_content(game.get_content());
+                }
+            }
+            Source::Server(scores) => {
+                if let Some(content) = scores.try_iter().last() {
+                    self.text.set_content(content);
+                }
+            }
+        }
+    }
+}
+
+impl Game {
+    /// takes in every event since last time, returning whether there's anything new to show
+    fn catch_up(&mut self) -> bool {
+        let mut changed = false;
+        for event in self.events.try_iter() {
+            match event {
+                GameEvent::ScoreChanged { player, score } => {
+                    self.players[player].score = score;
+                }
+                GameEvent::PowerUpsChanged { player, power_ups } => {
+                    self.players[player].power_ups = power_ups;
+                }
+                _ => continue,
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    fn get_content(&self) -> String {
+        let players = &self.players;
+        let mut scores = Vec::with_capacity(players.len());
+        for (i, player) in players.iter().enumerate() {
+            let mut score = if players.len() > 1 {
+                format!("player {}: {}", describe_player(i), player.score)
+            } else {
+                format!("ssscore: {}", player.score)
+            };
+            if let Some(bot) = self.bot {
+                score.push_str(&format!(" ({} playing)", bot.describe()));
+            }
+            if self.you == Some(i) {
+                score.push_str(" (you)");
+            }
+            for power_up in &player.power_ups {
+                score.push_str(&format!(
+                    "   {} {}",
+                    power_up.get_kind().describe(),
+                    power_up.get_ticks_left()
+                ));
+            }
+            scores.push(score);
+        }
+        let score = scores.join("      ");
+        match &self.level {
+            Some(level) => match level.get_par() {
+                Some(par) => format!("{}   {}   par: {}", level.get_name(), score, par),
+                None => format!("{}   {}", level.get_name(), score),
+            },
+            None => score,
+        }
+    }
+}
+
+impl ViewWrapper for ScoreView {
+    wrap_impl!(self.text: TextView);
+
+    fn wrap_required_size(&mut self, constraint: Vec2) -> Vec2 {
+        self.catch_up();
+        self.text.required_size(constraint)
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.catch_up();
+        self.text.layout(size);
+    }
+}
@@ -1,6 +1,148 @@
-use cursive::{views::Dialog, Cursive};
+use crate::core::{HighScore, HighScores, SortOrder};
+
+use cursive::{
+    views::{Dialog, DummyView, EditView, LinearLayout, NamedView, SelectView, TextView},
+    Cursive,
+};
+
+use std::rc::Rc;
+
+type BoardFilter = Option<(usize, usize)>;
 
 pub fn start(app: &mut Cursive) {
     tracing::info_span!("entering high score screen");
-    app.add_layer(Dialog::info("Not yet implemented!"))
+    let high_scores = match HighScores::default_path().and_then(HighScores::load) {
+        Ok(high_scores) => Rc::new(high_scores),
+        Err(e) => {
+            tracing::info!("could not load high scores: {}", e);
+            app.add_layer(Dialog::info(format!("could not load high sscores: {}", e)));
+            return;
+        }
+    };
+
+    let mut board_select = SelectView::<BoardFilter>::new().popup();
+    board_select.add_item("all boards", None);
+    for (width, height) in high_scores.get_board_sizes() {
+        board_select.add_item(format!("{}x{}", width, height), Some((width, height)));
+    }
+    let table = Rc::clone(&high_scores);
+    board_select.set_on_submit(move |s, _: &BoardFilter| refresh_table(s, &table));
+
+    let mut order_select = SelectView::<SortOrder>::new().popup();
+    for order in [SortOrder::HighestFirst, SortOrder::LowestFirst] {
+        order_select.add_item(order.describe(), order);
+    }
+    let table = Rc::clone(&high_scores);
+    order_select.set_on_submit(move |s, _: &SortOrder| refresh_table(s, &table));
+
+    let table_view = TextView::new(render_table(&high_scores, SortOrder::HighestFirst, None));
+
+    app.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(
+                    LinearLayout::horizontal()
+                        .child(TextView::new("board: "))
+                        .child(NamedView::new("high_score_board", board_select))
+                        .child(TextView::new("   sort: "))
+                        .child(NamedView::new("high_score_order", order_select)),
+                )
+                .child(DummyView)
+                .child(NamedView::new("high_score_table", table_view)),
+        )
+        .title("high sscores")
+        .button("back", |s| {
+            s.pop_layer();
+        }),
+    )
+}
+
+/// asks for a name to put next to a finished game's score,
+/// if the score is good enough to make the table
+pub fn prompt_for_name(app: &mut Cursive, high_score: HighScore) {
+    let high_scores = match HighScores::default_path().and_then(HighScores::load) {
+        Ok(high_scores) => high_scores,
+        Err(e) => {
+            tracing::info!("could not load high scores: {}", e);
+            return;
+        }
+    };
+    if !high_scores.qualifies(high_score.score, high_score.get_board_size()) {
+        return;
+    }
+    let submitted_score = high_score.clone();
+    let name_view = EditView::new()
+        .content(high_scores.get_last_name().unwrap_or_default())
+        .on_submit(move |s, name| save_high_score(s, &submitted_score, name));
+    app.add_layer(
+        Dialog::around(NamedView::new("high_score_name", name_view))
+            .title("new high sscore! what'sss your name?")
+            .button("ssave", move |s| {
+                let name = s
+                    .call_on_name("high_score_name", |v: &mut EditView| v.get_content())
+                    .unwrap_or_default();
+                save_high_score(s, &high_score, &name);
+            }),
+    );
+}
+
+fn save_high_score(app: &mut Cursive, high_score: &HighScore, name: &str) {
+    let name = match name.trim() {
+        "" => "anonymous snek",
+        name => name,
+    };
+    let mut high_score = high_score.clone();
+    high_score.name = name.to_string();
+    let result = HighScores::default_path().and_then(|path| {
+        let mut high_scores = HighScores::load(&path)?;
+        high_scores.add(high_score);
+        high_scores.save(path)
+    });
+    app.pop_layer();
+    if let Err(e) = result {
+        tracing::info!("could not save high score: {}", e);
+        app.add_layer(Dialog::info(format!("could not ssave high sscore: {}", e)));
+    }
+}
+
+fn refresh_table(app: &mut Cursive, high_scores: &HighScores) {
+    let board = app
+        .call_on_name("high_score_board", |v: &mut SelectView<BoardFilter>| {
+            v.selection()
+        })
+        .flatten()
+        .and_then(|board| *board);
+    let order = app
+        .call_on_name("high_score_order", |v: &mut SelectView<SortOrder>| {
+            v.selection()
+        })
+        .flatten()
+        .map_or(SortOrder::HighestFirst, |order| *order);
+    let table = render_table(high_scores, order, board);
+    app.call_on_name("high_score_table", |v: &mut TextView| v.set_content(table));
+}
+
+fn render_table(high_scores: &HighScores, order: SortOrder, board: BoardFilter) -> String {
+    let entries = high_scores.sorted(order, board);
+    if entries.is_empty() {
+        return "no high sscores yet. go play ssome snek!".to_string();
+    }
+    let mut table = format!(
+        "{:>3}  {:<16} {:>6} {:>7} {:>9}  {:<26} {}\n",
+        "#", "name", "score", "length", "board", "ending", "date"
+    );
+    for (i, high_score) in entries.iter().enumerate() {
+        let (width, height) = high_score.get_board_size();
+        table.push_str(&format!(
+            "{:>3}  {:<16} {:>6} {:>7} {:>9}  {:<26} {}\n",
+            i + 1,
+            high_score.name.chars().take(16).collect::<String>(),
+            high_score.score,
+            high_score.length,
+            format!("{}x{}", width, height),
+            high_score.describe_ending(),
+            high_score.describe_date()
+        ));
+    }
+    table
 }
@@ -1,16 +1,139 @@
 mod game;
 mod high_scores;
+mod stats;
 
-use cursive::{views::Dialog, Cursive};
+use crate::core::{Bot, GameOptions, Level};
+use crate::net::{Client, Peer};
+
+use cursive::{
+    views::{Dialog, SelectView},
+    Cursive,
+};
 
 pub fn title_screen(app: &mut Cursive) {
     tracing::info_span!("entering title screen");
     app.pop_layer();
+    let mut dialog = Dialog::text("\n\n   sso u want to play ssnek?..\n\n").title("snek");
+    if game::has_saved_game() {
+        dialog = dialog.button("continue", game::continue_game);
+    }
     app.add_layer(
-        Dialog::text("\n\n   sso u want to play ssnek?..\n\n")
-            .title("snek")
-            .button("sstart", game::new_game)
+        dialog
+            .button("sstart", choose_mode)
+            .button("watch the snek play", choose_bot)
             .button("high sscores", high_scores::start)
+            .button("ssstats", stats::start)
             .button("sstop", |app| app.quit()),
     );
 }
+
+fn choose_mode(app: &mut Cursive) {
+    let mut modes = SelectView::new();
+    modes.add_item("classsic", GameOptions::default());
+    modes.add_item("wraparound", GameOptions::wraparound());
+    modes.add_item("feasst", GameOptions::feast());
+    modes.add_item("orchard", GameOptions::orchard());
+    modes.add_item("power-upsss", GameOptions::power_ups());
+    modes.add_item("ssspeedy", GameOptions::speedy());
+    modes.add_item("arcade", GameOptions::arcade());
+    modes.add_item("two sssneks", GameOptions::two_player());
+    modes.set_on_submit(|app, options: &GameOptions| {
+        app.pop_layer();
+        game::new_game(app, *options);
+    });
+    app.add_layer(
+        Dialog::around(modes)
+            .title("pick a mode")
+            .button("levels", choose_level)
+            .button("back", |app| {
+                app.pop_layer();
+            }),
+    );
+}
+
+fn choose_bot(app: &mut Cursive) {
+    let mut bots = SelectView::new();
+    for bot in [Bot::Autopilot, Bot::Hamiltonian, Bot::Greedy] {
+        bots.add_item(bot.describe().to_string(), bot);
+    }
+    bots.set_on_submit(|app, bot: &Bot| {
+        app.pop_layer();
+        game::watch(app, *bot);
+    });
+    app.add_layer(
+        Dialog::around(bots)
+            .title("pick a bot")
+            .button("back", |app| {
+                app.pop_layer();
+            }),
+    );
+}
+
+/// a level from the list, or why it couldn't be loaded
+enum LevelChoice {
+    Ready(Level),
+    Broken(String),
+}
+
+fn choose_level(app: &mut Cursive) {
+    let mut levels = SelectView::new();
+    for level in Level::bundled() {
+        levels.add_item(level.get_name().to_string(), LevelChoice::Ready(level));
+    }
+    match Level::find_user_levels() {
+        Ok(paths) => {
+            for path in paths {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                match Level::load(&path) {
+                    Ok(level) => {
+                        levels.add_item(level.get_name().to_string(), LevelChoice::Ready(level))
+                    }
+                    Err(e) => levels.add_item(
+                        format!("{} (broken)", file_name),
+                        LevelChoice::Broken(format!("{} won't load:\n\n{}", file_name, e)),
+                    ),
+                }
+            }
+        }
+        Err(e) => tracing::info!("could not look for user levels: {}", e),
+    }
+    levels.set_on_submit(|app, choice: &LevelChoice| match choice {
+        LevelChoice::Ready(level) => {
+            app.pop_layer();
+            app.pop_layer();
+            game::play_level(app, level.clone());
+        }
+        LevelChoice::Broken(message) => app.add_layer(Dialog::info(message)),
+    });
+    app.add_layer(
+        Dialog::around(levels)
+            .title("pick a level")
+            .button("back", |app| {
+                app.pop_layer();
+            }),
+    );
+}
+
+/// joins a game on a server, going back to the title screen if that doesn't work
+pub fn connect(app: &mut Cursive, client: Client) {
+    title_screen(app);
+    game::connect(app, client);
+}
+
+/// watches a bot play, going back to the title screen afterwards
+pub fn watch(app: &mut Cursive, bot: Bot) {
+    title_screen(app);
+    game::watch(app, bot);
+}
+
+/// plays a game against another peer
+pub fn play_peer(app: &mut Cursive, peer: Peer) {
+    title_screen(app);
+    game::play_peer(app, peer);
+}
+
+/// quits the game, saving any game in progress first
+pub fn quit(app: &mut Cursive) {
+    game::autosave(app);
+    app.quit();
+}
@@ -0,0 +1,66 @@
+use crate::core::{Achievement, Stats};
+
+use cursive::{
+    views::{Dialog, TextView},
+    Cursive,
+};
+
+pub fn start(app: &mut Cursive) {
+    tracing::info_span!("entering stats screen");
+    let stats = match Stats::default_path().and_then(Stats::load) {
+        Ok(stats) => stats,
+        Err(e) => {
+            tracing::info!("could not load stats: {}", e);
+            app.add_layer(Dialog::info(format!("could not load ssstats: {}", e)));
+            return;
+        }
+    };
+    app.add_layer(
+        Dialog::around(TextView::new(render_stats(&stats)))
+            .title("ssstats")
+            .button("back", |s| {
+                s.pop_layer();
+            }),
+    )
+}
+
+fn render_stats(stats: &Stats) -> String {
+    let mut text = String::new();
+    for (name, count) in [
+        ("games played", stats.games_played),
+        ("apples eaten", stats.apples),
+        ("ticks alive", stats.ticks),
+        ("longest snek", stats.longest_snek),
+        ("worlds eaten", stats.worlds_eaten),
+    ] {
+        text.push_str(&format!("{:<28} {:>7}\n", name, count));
+    }
+    if !stats.deaths.is_empty() {
+        text.push_str("\nhow they died\n");
+        for (death_cause, count) in &stats.deaths {
+            text.push_str(&format!("  {:<26} {:>7}\n", death_cause.describe(), count));
+        }
+    }
+    text.push_str(&format!(
+        "\nachievements {}/{}\n",
+        stats.achievements.len(),
+        Achievement::ALL.len()
+    ));
+    for achievement in Achievement::ALL {
+        let line = match stats.describe_unlocked(achievement) {
+            Some(date) => format!(
+                "  ✓ {:<22} {}, {}\n",
+                achievement.get_name(),
+                achievement.describe(),
+                date
+            ),
+            None => format!(
+                "  · {:<22} {}\n",
+                achievement.get_name(),
+                achievement.describe()
+            ),
+        };
+        text.push_str(&line);
+    }
+    text
+}
@@ -4,7 +4,7 @@ mod tools;
 pub(crate) use tools::Runner;
 
 use ansi_term::Colour::Green;
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use camino::Utf8PathBuf;
 use lazy_static::lazy_static;
 use structopt::StructOpt;
@@ -15,8 +15,7 @@ lazy_static! {
 }
 
 fn project_root() -> Result<Utf8PathBuf> {
-    let manifest_dir = Utf8PathBuf::try_from(env!("CARGO_MANIFEST_DIR"))
-        .with_context(|| "Could not find the root directory.")?;
+    let manifest_dir = Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let root_dir = manifest_dir
         .ancestors()
         .nth(1)